@@ -0,0 +1,383 @@
+/// Árvore sintática abstrata (AST) da linguagem LSI-2024-2.
+///
+/// A AST é construída a partir da árvore de derivação produzida pelo parser preditivo.
+/// Os nós seguem as produções da gramática, descartando os símbolos auxiliares
+/// (`STMTLIST'`, `NUMEXPR'`, pontuação etc.) usados apenas para manter a gramática LL(1).
+use crate::parser::ParseTree;
+use crate::token::{Number, Span, TokenValue};
+
+/// Programa completo: uma lista de definições de função ou um único comando.
+#[derive(Debug)]
+pub struct Program {
+    pub functions: Vec<FuncDef>, // Funções definidas com `def` (MAIN -> FLIST)
+    pub statement: Option<Stmt>, // Comando avulso (MAIN -> STMT)
+}
+
+/// Identificador com a sua posição no código fonte.
+#[derive(Debug, Clone)]
+pub struct Ident {
+    pub name: String,
+    pub span: Span,
+}
+
+/// Definição de função: `def id ( PARLIST ) { STMTLIST }`.
+#[derive(Debug)]
+pub struct FuncDef {
+    pub name: Ident,
+    pub params: Vec<Ident>,
+    pub body: Vec<Stmt>,
+}
+
+/// Chamada de função: `id ( PARLISTCALL )`.
+#[derive(Debug)]
+pub struct FuncCall {
+    pub name: Ident,
+    pub args: Vec<Ident>,
+}
+
+/// Comandos da linguagem.
+#[derive(Debug)]
+pub enum Stmt {
+    /// `int VARLIST ;`
+    VarDecl(Vec<Ident>),
+    /// `id := EXPR ;` ou `id := FCALL ;`
+    Assign { target: Ident, value: Expr },
+    /// `print EXPR ;`
+    Print(Expr),
+    /// `return ;` ou `return id ;`
+    Return(Option<Ident>),
+    /// `if ( EXPR ) STMT` com ramo `else STMT` opcional
+    If {
+        cond: Expr,
+        then_branch: Box<Stmt>,
+        else_branch: Option<Box<Stmt>>,
+    },
+    /// `{ STMTLIST }`
+    Block(Vec<Stmt>),
+    /// `;`
+    Empty,
+}
+
+/// Operadores aritméticos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add, // +
+    Sub, // -
+    Mul, // *
+    Div, // /
+}
+
+/// Operadores relacionais.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelOp {
+    Lt, // <
+    Le, // <=
+    Gt, // >
+    Ge, // >=
+    Eq, // ==
+    Ne, // <>
+}
+
+/// Expressões da linguagem.
+#[derive(Debug)]
+pub enum Expr {
+    Num {
+        value: Number, // Na largura de `LexerOptions::integer_width`
+        span: Span,
+    },
+    Var(Ident),
+    Binary {
+        op: BinOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Relational {
+        op: RelOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Call(FuncCall),
+}
+
+/// Valor de um literal da AST como inteiro de 32 bits, a largura usada na execução e na
+/// geração de código.
+///
+/// # Pânico
+/// Entra em pânico se o literal não couber em 32 bits; o programa deve ter passado pela
+/// análise semântica (`semantic::check`) sem erros.
+pub fn literal_value(value: &Number) -> i32 {
+    value
+        .to_i32()
+        .unwrap_or_else(|| panic!("literal `{}` fora do intervalo de 32 bits", value))
+}
+
+impl BinOp {
+    fn from_symbol(symbol: &str) -> Option<BinOp> {
+        match symbol {
+            "+" => Some(BinOp::Add),
+            "-" => Some(BinOp::Sub),
+            "*" => Some(BinOp::Mul),
+            "/" => Some(BinOp::Div),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+        }
+    }
+}
+
+impl RelOp {
+    fn from_symbol(symbol: &str) -> Option<RelOp> {
+        match symbol {
+            "<" => Some(RelOp::Lt),
+            "<=" => Some(RelOp::Le),
+            ">" => Some(RelOp::Gt),
+            ">=" => Some(RelOp::Ge),
+            "==" => Some(RelOp::Eq),
+            "<>" => Some(RelOp::Ne),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelOp::Lt => "<",
+            RelOp::Le => "<=",
+            RelOp::Gt => ">",
+            RelOp::Ge => ">=",
+            RelOp::Eq => "==",
+            RelOp::Ne => "<>",
+        }
+    }
+}
+
+/// Constrói a AST a partir de uma árvore de derivação completa de `MAIN`.
+///
+/// # Pânico
+/// Entra em pânico se a árvore não seguir as produções da gramática LSI-2024-2,
+/// o que indica um erro no parser e não no programa analisado.
+pub fn build(tree: &ParseTree) -> Program {
+    let builder = Builder { tree };
+    builder.program(0)
+}
+
+/// Não terminais das listas recursivas da gramática, percorridos por `Builder::collect`.
+const LISTS: &[&str] = &[
+    "FLIST",
+    "FLIST'",
+    "PARLIST",
+    "PARLIST'",
+    "VARLIST",
+    "VARLIST'",
+    "PARLISTCALL",
+    "PARLISTCALL'",
+    "STMTLIST",
+    "STMTLIST'",
+];
+
+struct Builder<'a> {
+    tree: &'a ParseTree,
+}
+
+impl Builder<'_> {
+    fn symbol(&self, node: usize) -> &str {
+        &self.tree.node(node).symbol
+    }
+
+    fn children(&self, node: usize) -> &[usize] {
+        &self.tree.node(node).children
+    }
+
+    /// Filho de `node` cujo símbolo é `symbol`.
+    fn child(&self, node: usize, symbol: &str) -> Option<usize> {
+        self.children(node)
+            .iter()
+            .copied()
+            .find(|&child| self.symbol(child) == symbol)
+    }
+
+    fn ident(&self, node: usize) -> Ident {
+        let token = self
+            .tree
+            .token(node)
+            .expect("terminal `id` sem token correspondente");
+        Ident {
+            name: token.lexeme.clone(),
+            span: token.span,
+        }
+    }
+
+    /// Coleta, em ordem, todos os nós com símbolo `symbol` nas listas recursivas (`LISTS`).
+    fn collect(&self, node: usize, symbol: &str, out: &mut Vec<usize>) {
+        for &child in self.children(node) {
+            let child_symbol = self.symbol(child);
+            if child_symbol == symbol {
+                out.push(child);
+            } else if LISTS.contains(&child_symbol) {
+                self.collect(child, symbol, out);
+            }
+        }
+    }
+
+    fn collect_idents(&self, node: usize) -> Vec<Ident> {
+        let mut ids = vec![];
+        self.collect(node, "id", &mut ids);
+        ids.into_iter().map(|id| self.ident(id)).collect()
+    }
+
+    fn collect_stmts(&self, node: usize) -> Vec<Stmt> {
+        let mut stmts = vec![];
+        self.collect(node, "STMT", &mut stmts);
+        stmts.into_iter().map(|stmt| self.stmt(stmt)).collect()
+    }
+
+    // MAIN -> STMT | FLIST | ε
+    fn program(&self, node: usize) -> Program {
+        let mut functions = vec![];
+        let mut statement = None;
+
+        if let Some(stmt) = self.child(node, "STMT") {
+            statement = Some(self.stmt(stmt));
+        } else if let Some(flist) = self.child(node, "FLIST") {
+            let mut defs = vec![];
+            self.collect(flist, "FDEF", &mut defs);
+            functions = defs.into_iter().map(|def| self.func_def(def)).collect();
+        }
+
+        Program {
+            functions,
+            statement,
+        }
+    }
+
+    // FDEF -> def id ( PARLIST ) { STMTLIST }
+    fn func_def(&self, node: usize) -> FuncDef {
+        let name = self.ident(self.child(node, "id").unwrap());
+        let params = self.collect_idents(self.child(node, "PARLIST").unwrap());
+        let body = self.collect_stmts(self.child(node, "STMTLIST").unwrap());
+        FuncDef { name, params, body }
+    }
+
+    // STMT -> int VARLIST ; | ATRIBST ; | PRINTST ; | RETURNST ; | IFSTMT | { STMTLIST } | ;
+    fn stmt(&self, node: usize) -> Stmt {
+        let first = self.children(node)[0];
+        match self.symbol(first) {
+            "int" => Stmt::VarDecl(self.collect_idents(self.child(node, "VARLIST").unwrap())),
+            "ATRIBST" => self.assign(first),
+            "PRINTST" => Stmt::Print(self.expr(self.child(first, "EXPR").unwrap())),
+            "RETURNST" => {
+                // RETURNST -> return RETURNST' ; RETURNST' -> id | ε
+                let tail = self.child(first, "RETURNST'").unwrap();
+                Stmt::Return(self.child(tail, "id").map(|id| self.ident(id)))
+            }
+            "IFSTMT" => self.if_stmt(first),
+            "{" => Stmt::Block(self.collect_stmts(self.child(node, "STMTLIST").unwrap())),
+            ";" => Stmt::Empty,
+            symbol => unreachable!("produção inesperada para STMT: {}", symbol),
+        }
+    }
+
+    // ATRIBST -> id := ATRIBST' ; ATRIBST' -> EXPR | FCALL
+    fn assign(&self, node: usize) -> Stmt {
+        let target = self.ident(self.child(node, "id").unwrap());
+        let tail = self.child(node, "ATRIBST'").unwrap();
+        let value = match self.child(tail, "FCALL") {
+            Some(call) => Expr::Call(self.func_call(call)),
+            None => self.expr(self.child(tail, "EXPR").unwrap()),
+        };
+        Stmt::Assign { target, value }
+    }
+
+    // IFSTMT -> if ( EXPR ) STMT IFSTMT' ; IFSTMT' -> else STMT | ε
+    fn if_stmt(&self, node: usize) -> Stmt {
+        let cond = self.expr(self.child(node, "EXPR").unwrap());
+        let then_branch = Box::new(self.stmt(self.child(node, "STMT").unwrap()));
+        let tail = self.child(node, "IFSTMT'").unwrap();
+        let else_branch = self
+            .child(tail, "STMT")
+            .map(|stmt| Box::new(self.stmt(stmt)));
+        Stmt::If {
+            cond,
+            then_branch,
+            else_branch,
+        }
+    }
+
+    // FCALL -> id ( PARLISTCALL )
+    fn func_call(&self, node: usize) -> FuncCall {
+        FuncCall {
+            name: self.ident(self.child(node, "id").unwrap()),
+            args: self.collect_idents(self.child(node, "PARLISTCALL").unwrap()),
+        }
+    }
+
+    // EXPR -> NUMEXPR EXPR' ; EXPR' -> relop NUMEXPR | ε
+    fn expr(&self, node: usize) -> Expr {
+        let lhs = self.arith(self.child(node, "NUMEXPR").unwrap());
+        let tail = self.child(node, "EXPR'").unwrap();
+        match self.children(tail) {
+            [op, rhs] => Expr::Relational {
+                op: RelOp::from_symbol(self.symbol(*op)).unwrap(),
+                lhs: Box::new(lhs),
+                rhs: Box::new(self.arith(*rhs)),
+            },
+            _ => lhs,
+        }
+    }
+
+    // NUMEXPR -> TERM NUMEXPR' ; NUMEXPR' -> + TERM NUMEXPR' | - TERM NUMEXPR' | ε
+    // TERM -> FACT TERM' ; TERM' -> * FACT TERM' | / FACT TERM' | ε
+    // As produções recursivas à direita são dobradas à esquerda para manter a associatividade.
+    fn arith(&self, node: usize) -> Expr {
+        let &[operand, mut tail] = self.children(node) else {
+            unreachable!("produção inesperada para {}", self.symbol(node));
+        };
+        let mut expr = self.operand(operand);
+
+        while let &[op, rhs, next] = self.children(tail) {
+            expr = Expr::Binary {
+                op: BinOp::from_symbol(self.symbol(op)).unwrap(),
+                lhs: Box::new(expr),
+                rhs: Box::new(self.operand(rhs)),
+            };
+            tail = next;
+        }
+
+        expr
+    }
+
+    fn operand(&self, node: usize) -> Expr {
+        match self.symbol(node) {
+            "TERM" => self.arith(node),
+            "FACT" => self.fact(node),
+            symbol => unreachable!("operando inesperado: {}", symbol),
+        }
+    }
+
+    // FACT -> num | ( EXPR ) | id
+    fn fact(&self, node: usize) -> Expr {
+        let first = self.children(node)[0];
+        match self.symbol(first) {
+            "num" => {
+                let token = self.tree.token(first).unwrap();
+                let value = match &token.value {
+                    TokenValue::Number(number) => number.clone(),
+                    value => unreachable!("token `num` sem valor numérico: {:?}", value),
+                };
+                Expr::Num {
+                    value,
+                    span: token.span,
+                }
+            }
+            "id" => Expr::Var(self.ident(first)),
+            "(" => self.expr(self.child(node, "EXPR").unwrap()),
+            symbol => unreachable!("produção inesperada para FACT: {}", symbol),
+        }
+    }
+}
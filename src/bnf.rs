@@ -0,0 +1,379 @@
+/// Leitura de gramáticas descritas em arquivos texto no formato BNF.
+///
+/// # Formato
+/// ```text
+/// # Comentário até o fim da linha
+/// %start MAIN                      (opcional; o padrão é o primeiro não terminal definido)
+/// STMT -> int VARLIST ; | ATRIBST ;
+///       | PRINTST ;                linhas iniciadas por `|` continuam a regra anterior
+/// RETURNST' -> id | ε              `ε` representa a alternativa vazia
+/// %prefer IFSTMT' else -> else STMT
+/// %lookahead ATRIBST' id ( -> FCALL
+/// %describe VARLIST' após a lista de variáveis
+/// ```
+///
+/// Os símbolos são separados por espaços. Símbolos que aparecem do lado esquerdo de uma
+/// regra são não terminais; os demais são terminais e precisam ser reconhecidos pelo
+/// analisador léxico: os terminais da LSI-2024-2 (`id`, `num`, operadores e pontuação)
+/// ou palavras, que passam a ser palavras reservadas.
+///
+/// As diretivas `%prefer` e `%lookahead` declaram a resolução de conflitos LL(1)
+/// (ver `Grammar::prefer` e `Grammar::lookahead`), e `%describe` declara o contexto usado nas
+/// mensagens de erro sintático quando o não terminal está no topo da pilha (ver `Grammar::describe`).
+use std::collections::{HashMap, HashSet};
+
+use crate::diagnostic::Diagnostic;
+use crate::grammar::{Grammar, Resolution, EPSILON};
+use crate::token::{Span, Terminal};
+
+/// Diretiva lida do arquivo.
+enum Directive {
+    Prefer {
+        nonterminal: String,
+        terminal: String,
+        alternative: String,
+    },
+    Lookahead {
+        nonterminal: String,
+        tokens: Vec<String>,
+        alternative: String,
+    },
+    Describe {
+        nonterminal: String,
+        description: String,
+    },
+}
+
+/// Lê e valida uma gramática no formato BNF.
+///
+/// # Retorna
+/// - `Ok((Grammar, Vec<Diagnostic>))`: A gramática e os avisos encontrados na validação.
+/// - `Err(Vec<Diagnostic>)`: Todos os erros e avisos, se houver ao menos um erro.
+pub fn parse_grammar(source: &str) -> Result<(Grammar, Vec<Diagnostic>), Vec<Diagnostic>> {
+    let mut diagnostics = vec![];
+    let mut start: Option<String> = None;
+    let mut rules: Vec<(String, String)> = vec![]; // (lado esquerdo, alternativa)
+    let mut directives: Vec<(Directive, Span)> = vec![];
+    let mut current: Option<String> = None;
+
+    // Primeira linha em que cada símbolo é definido ou usado, para os diagnósticos
+    let mut defined_at: HashMap<String, Span> = HashMap::new();
+    let mut used_at: HashMap<String, Span> = HashMap::new();
+
+    let mut offset = 0;
+    for (number, raw_line) in source.split('\n').enumerate() {
+        let span = Span {
+            start: offset,
+            end: offset + raw_line.trim_end().len(),
+            line: number + 1,
+            column: 1,
+        };
+        offset += raw_line.len() + 1;
+
+        let line = raw_line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(directive) = line.strip_prefix('%') {
+            let words: Vec<&str> = directive.split_whitespace().collect();
+            match words.as_slice() {
+                ["start", symbol] => start = Some(symbol.to_string()),
+                ["prefer", nonterminal, terminal, "->", alternative @ ..] => directives.push((
+                    Directive::Prefer {
+                        nonterminal: nonterminal.to_string(),
+                        terminal: terminal.to_string(),
+                        alternative: alternative.join(" "),
+                    },
+                    span,
+                )),
+                ["lookahead", nonterminal, rest @ ..] => {
+                    match rest.iter().position(|word| *word == "->") {
+                        Some(arrow) if arrow > 0 => directives.push((
+                            Directive::Lookahead {
+                                nonterminal: nonterminal.to_string(),
+                                tokens: rest[..arrow].iter().map(|t| t.to_string()).collect(),
+                                alternative: rest[arrow + 1..].join(" "),
+                            },
+                            span,
+                        )),
+                        _ => diagnostics.push(Diagnostic::error(
+                            "diretiva `%lookahead` mal formada; use `%lookahead A t1 t2 -> alternativa`",
+                            span,
+                        )),
+                    }
+                }
+                ["describe", nonterminal, description @ ..] if !description.is_empty() => {
+                    directives.push((
+                        Directive::Describe {
+                            nonterminal: nonterminal.to_string(),
+                            description: description.join(" "),
+                        },
+                        span,
+                    ))
+                }
+                _ => diagnostics.push(Diagnostic::error(
+                    format!("diretiva desconhecida ou mal formada: `%{}`", directive),
+                    span,
+                )),
+            }
+            continue;
+        }
+
+        let alternatives = if let Some(rest) = line.strip_prefix('|') {
+            if current.is_none() {
+                diagnostics.push(Diagnostic::error(
+                    "alternativa `|` sem uma regra anterior",
+                    span,
+                ));
+                continue;
+            }
+            rest
+        } else if let Some((lhs, rest)) = line.split_once("->") {
+            let lhs: Vec<&str> = lhs.split_whitespace().collect();
+            if lhs.len() != 1 {
+                diagnostics.push(Diagnostic::error(
+                    "o lado esquerdo de uma regra deve ser um único não terminal",
+                    span,
+                ));
+                current = None;
+                continue;
+            }
+            current = Some(lhs[0].to_string());
+            defined_at.entry(lhs[0].to_string()).or_insert(span);
+            rest
+        } else {
+            diagnostics.push(Diagnostic::error(
+                format!("regra sem `->`: `{}`", line),
+                span,
+            ));
+            continue;
+        };
+
+        let lhs = current.clone().unwrap();
+        for alternative in alternatives.split('|') {
+            let symbols: Vec<&str> = alternative.split_whitespace().collect();
+            if symbols.is_empty() {
+                diagnostics.push(Diagnostic::error(
+                    format!("alternativa vazia em `{}`; use `{}`", lhs, EPSILON),
+                    span,
+                ));
+                continue;
+            }
+            for symbol in &symbols {
+                used_at.entry(symbol.to_string()).or_insert(span);
+            }
+            rules.push((lhs.clone(), symbols.join(" ")));
+        }
+    }
+
+    if rules.is_empty() {
+        diagnostics.push(Diagnostic::error(
+            "a gramática não possui nenhuma regra",
+            Span {
+                line: 1,
+                column: 1,
+                ..Span::default()
+            },
+        ));
+        return Err(diagnostics);
+    }
+
+    let start = start.unwrap_or_else(|| rules[0].0.clone());
+    let mut grammar = Grammar::new(&start);
+    for (lhs, alternative) in &rules {
+        grammar.add(lhs, &[alternative.as_str()]);
+    }
+
+    for (directive, span) in directives {
+        match directive {
+            Directive::Prefer {
+                nonterminal,
+                terminal,
+                alternative,
+            } => {
+                if grammar
+                    .find_production(&nonterminal, &alternative)
+                    .is_some()
+                {
+                    grammar.prefer(&nonterminal, &terminal, &alternative);
+                } else {
+                    diagnostics.push(undeclared(&nonterminal, &alternative, span));
+                }
+            }
+            Directive::Lookahead {
+                nonterminal,
+                tokens,
+                alternative,
+            } => {
+                if grammar
+                    .find_production(&nonterminal, &alternative)
+                    .is_some()
+                {
+                    let tokens: Vec<&str> = tokens.iter().map(String::as_str).collect();
+                    grammar.lookahead(&nonterminal, &tokens, &alternative);
+                } else {
+                    diagnostics.push(undeclared(&nonterminal, &alternative, span));
+                }
+            }
+            Directive::Describe {
+                nonterminal,
+                description,
+            } => {
+                if grammar.is_nonterminal(&nonterminal) {
+                    grammar.describe(&nonterminal, &description);
+                } else {
+                    diagnostics.push(Diagnostic::error(
+                        format!(
+                            "a diretiva `%describe` se refere a `{}`, que não é um não terminal da gramática",
+                            nonterminal
+                        ),
+                        span,
+                    ));
+                }
+            }
+        }
+    }
+
+    diagnostics.extend(validate(&grammar, &defined_at, &used_at));
+
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        Err(diagnostics)
+    } else {
+        Ok((grammar, diagnostics))
+    }
+}
+
+/// Palavras reservadas da gramática: terminais formados por letras e dígitos,
+/// exceto as classes de token `id` e `num`.
+pub fn grammar_keywords(grammar: &Grammar) -> HashSet<String> {
+    grammar
+        .productions
+        .iter()
+        .flat_map(|production| production.rhs.iter())
+        .filter(|symbol| !grammar.is_nonterminal(symbol) && is_word(symbol))
+        .filter(|symbol| *symbol != "id" && *symbol != "num")
+        .cloned()
+        .collect()
+}
+
+fn undeclared(nonterminal: &str, alternative: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(
+        format!(
+            "a diretiva se refere à produção `{} -> {}`, que não foi declarada",
+            nonterminal, alternative
+        ),
+        span,
+    )
+}
+
+fn is_word(symbol: &str) -> bool {
+    let mut chars = symbol.chars();
+    chars.next().is_some_and(|ch| ch.is_ascii_alphabetic())
+        && chars.all(|ch| ch.is_ascii_alphanumeric())
+}
+
+/// Símbolos escritos como não terminais: letras maiúsculas, dígitos, `_` e `'`.
+fn looks_like_nonterminal(symbol: &str) -> bool {
+    symbol.chars().any(|ch| ch.is_ascii_uppercase())
+        && symbol
+            .chars()
+            .all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit() || ch == '_' || ch == '\'')
+}
+
+/// Verifica se a gramática pode ser usada pelo parser preditivo.
+fn validate(
+    grammar: &Grammar,
+    defined_at: &HashMap<String, Span>,
+    used_at: &HashMap<String, Span>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let first_line = Span {
+        line: 1,
+        column: 1,
+        ..Span::default()
+    };
+    let defined = |symbol: &str| defined_at.get(symbol).copied().unwrap_or(first_line);
+
+    if !grammar.is_nonterminal(&grammar.start) {
+        diagnostics.push(Diagnostic::error(
+            format!("o símbolo inicial `{}` não possui produções", grammar.start),
+            first_line,
+        ));
+    }
+
+    // Terminais precisam ser produzidos pelo analisador léxico
+    let mut terminals: Vec<(&String, &Span)> = used_at
+        .iter()
+        .filter(|(symbol, _)| !grammar.is_nonterminal(symbol) && *symbol != EPSILON)
+        .collect();
+    terminals.sort_by_key(|(_, span)| span.start);
+    for (symbol, span) in terminals {
+        if looks_like_nonterminal(symbol) {
+            diagnostics.push(Diagnostic::error(
+                format!(
+                    "o não terminal `{}` é usado mas não possui produções",
+                    symbol
+                ),
+                *span,
+            ));
+        } else if !is_word(symbol)
+            && !matches!(Terminal::from_str(symbol), Some(t) if t != Terminal::Epsilon && t != Terminal::DollarSign)
+        {
+            diagnostics.push(Diagnostic::error(
+                format!(
+                    "o terminal `{}` não é reconhecido pelo analisador léxico",
+                    symbol
+                ),
+                *span,
+            ));
+        }
+    }
+
+    for nonterminal in grammar.nonproductive() {
+        diagnostics.push(Diagnostic::error(
+            format!(
+                "o não terminal `{}` não deriva nenhuma cadeia de terminais",
+                nonterminal
+            ),
+            defined(nonterminal),
+        ));
+    }
+
+    for nonterminal in grammar.left_recursive() {
+        diagnostics.push(Diagnostic::error(
+            format!(
+                "o não terminal `{}` possui recursão à esquerda e não pode ser analisado por um parser preditivo",
+                nonterminal
+            ),
+            defined(nonterminal),
+        ));
+    }
+
+    for nonterminal in grammar.unreachable() {
+        diagnostics.push(Diagnostic::warning(
+            format!(
+                "o não terminal `{}` não é alcançável a partir de `{}`",
+                nonterminal, grammar.start
+            ),
+            defined(nonterminal),
+        ));
+    }
+
+    // Conflitos sem diretiva são resolvidos pela ordem, o que pode não ser intencional
+    if diagnostics.iter().all(|diagnostic| !diagnostic.is_error()) {
+        for conflict in grammar.conflicts() {
+            if let Resolution::DeclarationOrder = conflict.resolution {
+                diagnostics.push(Diagnostic::warning(
+                    format!(
+                        "{} (use `%prefer` ou `%lookahead` para declarar a resolução)",
+                        conflict.to_string().replace('\n', " ")
+                    ),
+                    defined(&conflict.nonterminal),
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
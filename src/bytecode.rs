@@ -0,0 +1,465 @@
+/// Bytecode de pilha da linguagem LSI-2024-2.
+///
+/// Cada função é compilada, a partir do código de três endereços (`tac`), para uma sequência
+/// de bytes: um byte de opcode seguido dos operandos em little-endian. As variáveis da função
+/// (parâmetros, locais e temporários) ocupam posições numeradas (`slots`), e os parâmetros
+/// ocupam as primeiras posições. Os valores são inteiros de 32 bits empilhados na pilha de
+/// operandos da máquina virtual (`vm`).
+///
+/// | Opcode          | Operandos               | Efeito                                           |
+/// |-----------------|-------------------------|--------------------------------------------------|
+/// | `PUSH`          | `i32` valor             | empilha o valor                                  |
+/// | `LOAD`          | `u16` slot              | empilha a variável                               |
+/// | `STORE`         | `u16` slot              | desempilha para a variável                       |
+/// | `ADD` ... `DIV` |                         | desempilha `b` e `a`, empilha `a op b`           |
+/// | `CMP`           | `u8` operador           | desempilha `b` e `a`, empilha 1 ou 0             |
+/// | `JUMP`          | `u32` destino           | desvia para o offset                             |
+/// | `JUMP_IF_FALSE` | `u32` destino           | desempilha e desvia se o valor for 0             |
+/// | `CALL`          | `u16` função, `u8` argc | desempilha os argumentos e chama a função        |
+/// | `RET`           |                         | desempilha o valor de retorno e volta ao chamador |
+/// | `PRINT`         |                         | desempilha e imprime o valor                     |
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::ast::{BinOp, RelOp};
+use crate::tac::{Function, Instr, Operand, TacProgram};
+
+/// Códigos das instruções.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Opcode {
+    Push = 0x01,
+    Load = 0x02,
+    Store = 0x03,
+    Add = 0x10,
+    Sub = 0x11,
+    Mul = 0x12,
+    Div = 0x13,
+    Cmp = 0x14,
+    Jump = 0x20,
+    JumpIfFalse = 0x21,
+    Call = 0x30,
+    Ret = 0x31,
+    Print = 0x40,
+}
+
+impl Opcode {
+    pub fn from_byte(byte: u8) -> Option<Opcode> {
+        [
+            Opcode::Push,
+            Opcode::Load,
+            Opcode::Store,
+            Opcode::Add,
+            Opcode::Sub,
+            Opcode::Mul,
+            Opcode::Div,
+            Opcode::Cmp,
+            Opcode::Jump,
+            Opcode::JumpIfFalse,
+            Opcode::Call,
+            Opcode::Ret,
+            Opcode::Print,
+        ]
+        .into_iter()
+        .find(|opcode| *opcode as u8 == byte)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Opcode::Push => "PUSH",
+            Opcode::Load => "LOAD",
+            Opcode::Store => "STORE",
+            Opcode::Add => "ADD",
+            Opcode::Sub => "SUB",
+            Opcode::Mul => "MUL",
+            Opcode::Div => "DIV",
+            Opcode::Cmp => "CMP",
+            Opcode::Jump => "JUMP",
+            Opcode::JumpIfFalse => "JUMP_IF_FALSE",
+            Opcode::Call => "CALL",
+            Opcode::Ret => "RET",
+            Opcode::Print => "PRINT",
+        }
+    }
+}
+
+/// Codificação do operador relacional de `CMP`.
+pub fn relop_to_byte(op: RelOp) -> u8 {
+    match op {
+        RelOp::Lt => 0,
+        RelOp::Le => 1,
+        RelOp::Gt => 2,
+        RelOp::Ge => 3,
+        RelOp::Eq => 4,
+        RelOp::Ne => 5,
+    }
+}
+
+/// Operador relacional codificado em `byte`.
+pub fn relop_from_byte(byte: u8) -> Option<RelOp> {
+    [
+        RelOp::Lt,
+        RelOp::Le,
+        RelOp::Gt,
+        RelOp::Ge,
+        RelOp::Eq,
+        RelOp::Ne,
+    ]
+    .get(byte as usize)
+    .copied()
+}
+
+/// Função compilada.
+#[derive(Debug, Clone)]
+pub struct CodeFunction {
+    pub name: String,            // Nome da função
+    pub arity: usize,            // Número de parâmetros (os primeiros slots)
+    pub slot_names: Vec<String>, // Nome de cada slot, usado pelo disassembler
+    pub code: Vec<u8>,           // Bytecode
+}
+
+/// Programa compilado: as funções são referenciadas por `CALL` pelo índice neste vetor.
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub functions: Vec<CodeFunction>,
+}
+
+impl Module {
+    /// Índice da função `name`.
+    pub fn function_index(&self, name: &str) -> Option<usize> {
+        self.functions
+            .iter()
+            .position(|function| function.name == name)
+    }
+}
+
+/// Compila o programa em código de três endereços para bytecode.
+///
+/// # Retorna
+/// - `Ok(Module)`: O programa compilado.
+/// - `Err(String)`: Uma chamada a uma função não definida ou o limite do formato excedido: mais
+///   de 65536 funções, mais de 65536 slots em uma função, mais de 255 argumentos em uma chamada
+///   ou funções com mais de 4 GiB de código.
+pub fn compile(program: &TacProgram) -> Result<Module, String> {
+    if program.functions.len() > MAX_INDEX {
+        return Err(format!(
+            "o programa tem {} funções; o bytecode aceita no máximo {}",
+            program.functions.len(),
+            MAX_INDEX
+        ));
+    }
+    let indices: HashMap<&str, u16> = program
+        .functions
+        .iter()
+        .enumerate()
+        .map(|(index, function)| (function.name.as_str(), index as u16))
+        .collect();
+
+    let functions = program
+        .functions
+        .iter()
+        .map(|function| compile_function(function, &indices))
+        .collect::<Result<Vec<CodeFunction>, String>>()?;
+    Ok(Module { functions })
+}
+
+/// Quantidade máxima de funções e de slots por função, endereçados por um `u16`.
+const MAX_INDEX: usize = u16::MAX as usize + 1;
+
+fn compile_function(
+    function: &Function,
+    indices: &HashMap<&str, u16>,
+) -> Result<CodeFunction, String> {
+    // Parâmetros, locais e temporários, nessa ordem
+    let mut slot_names: Vec<String> = function.params.clone();
+    slot_names.extend(function.locals.iter().cloned());
+    slot_names.extend((1..=function.temps).map(|index| format!("t{}", index)));
+    if slot_names.len() > MAX_INDEX {
+        return Err(format!(
+            "a função `{}` tem {} variáveis e temporários; o bytecode aceita no máximo {}",
+            function.name,
+            slot_names.len(),
+            MAX_INDEX
+        ));
+    }
+    // Temporários são indexados à parte, já que uma variável pode se chamar `t1`
+    let temps = function.params.len() + function.locals.len();
+    let slots: HashMap<String, u16> = slot_names[..temps]
+        .iter()
+        .enumerate()
+        .map(|(slot, name)| (name.clone(), slot as u16))
+        .collect();
+
+    let mut emitter = Emitter {
+        code: vec![],
+        slots: &slots,
+        temps,
+        labels: HashMap::new(),
+        fixups: vec![],
+    };
+
+    for instr in &function.body {
+        match instr {
+            Instr::Copy { dest, src } => {
+                emitter.load(src);
+                emitter.store(dest);
+            }
+            Instr::Binary { dest, op, lhs, rhs } => {
+                emitter.load(lhs);
+                emitter.load(rhs);
+                emitter.code.push(match op {
+                    BinOp::Add => Opcode::Add,
+                    BinOp::Sub => Opcode::Sub,
+                    BinOp::Mul => Opcode::Mul,
+                    BinOp::Div => Opcode::Div,
+                } as u8);
+                emitter.store(dest);
+            }
+            Instr::Relational { dest, op, lhs, rhs } => {
+                emitter.load(lhs);
+                emitter.load(rhs);
+                emitter.code.extend([Opcode::Cmp as u8, relop_to_byte(*op)]);
+                emitter.store(dest);
+            }
+            Instr::IfFalse {
+                op,
+                lhs,
+                rhs,
+                target,
+            } => {
+                emitter.load(lhs);
+                emitter.load(rhs);
+                emitter.code.extend([Opcode::Cmp as u8, relop_to_byte(*op)]);
+                emitter.jump(Opcode::JumpIfFalse, *target);
+            }
+            Instr::Goto(target) => emitter.jump(Opcode::Jump, *target),
+            Instr::Label(label) => {
+                emitter.labels.insert(*label, emitter.code.len() as u32);
+            }
+            Instr::Param(arg) => emitter.load(arg),
+            Instr::Call {
+                dest,
+                function: callee,
+                argc,
+            } => {
+                let Ok(argc) = u8::try_from(*argc) else {
+                    return Err(format!(
+                        "a chamada a `{}` em `{}` tem {} argumentos; o bytecode aceita no máximo {}",
+                        callee,
+                        function.name,
+                        argc,
+                        u8::MAX
+                    ));
+                };
+                let Some(index) = indices.get(callee.as_str()) else {
+                    return Err(format!(
+                        "a função `{}` chama `{}`, que não foi definida",
+                        function.name, callee
+                    ));
+                };
+                emitter.code.push(Opcode::Call as u8);
+                emitter.code.extend(index.to_le_bytes());
+                emitter.code.push(argc);
+                emitter.store(dest);
+            }
+            Instr::Return(value) => {
+                emitter.load(value.as_ref().unwrap_or(&Operand::Const(0)));
+                emitter.code.push(Opcode::Ret as u8);
+            }
+            Instr::Print(value) => {
+                emitter.load(value);
+                emitter.code.push(Opcode::Print as u8);
+            }
+        }
+    }
+
+    // Os destinos dos desvios são offsets `u32`
+    if u32::try_from(emitter.code.len()).is_err() {
+        return Err(format!(
+            "a função `{}` tem {} bytes de código; o bytecode aceita no máximo {}",
+            function.name,
+            emitter.code.len(),
+            u32::MAX
+        ));
+    }
+
+    // Os rótulos só são conhecidos depois de emitir a função inteira
+    for (position, label) in emitter.fixups {
+        let target = emitter.labels[&label];
+        emitter.code[position..position + 4].copy_from_slice(&target.to_le_bytes());
+    }
+
+    Ok(CodeFunction {
+        name: function.name.clone(),
+        arity: function.params.len(),
+        slot_names,
+        code: emitter.code,
+    })
+}
+
+/// Estado da compilação de uma função.
+struct Emitter<'a> {
+    code: Vec<u8>,
+    slots: &'a HashMap<String, u16>,
+    temps: usize,                // Slot do primeiro temporário
+    labels: HashMap<usize, u32>, // Rótulo da TAC -> offset no bytecode
+    fixups: Vec<(usize, usize)>, // (offset do operando de um desvio, rótulo de destino)
+}
+
+impl Emitter<'_> {
+    fn load(&mut self, operand: &Operand) {
+        match operand {
+            Operand::Const(value) => {
+                self.code.push(Opcode::Push as u8);
+                self.code.extend(value.to_le_bytes());
+            }
+            _ => {
+                self.code.push(Opcode::Load as u8);
+                self.code.extend(self.slot(operand).to_le_bytes());
+            }
+        }
+    }
+
+    fn store(&mut self, operand: &Operand) {
+        self.code.push(Opcode::Store as u8);
+        self.code.extend(self.slot(operand).to_le_bytes());
+    }
+
+    fn jump(&mut self, opcode: Opcode, label: usize) {
+        self.code.push(opcode as u8);
+        self.fixups.push((self.code.len(), label));
+        self.code.extend(0u32.to_le_bytes());
+    }
+
+    fn slot(&self, operand: &Operand) -> u16 {
+        match operand {
+            Operand::Var(name) => self.slots[name],
+            // Cabe em um `u16`: `compile_function` limita a quantidade de slots
+            Operand::Temp(index) => (self.temps + *index - 1) as u16,
+            Operand::Const(_) => unreachable!("constantes não ocupam slots"),
+        }
+    }
+}
+
+/// Instrução decodificada do bytecode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decoded {
+    Push(i32),
+    Load(u16),
+    Store(u16),
+    Arith(Opcode),
+    Cmp(RelOp),
+    Jump(u32),
+    JumpIfFalse(u32),
+    Call { function: u16, argc: u8 },
+    Ret,
+    Print,
+}
+
+/// Decodifica a instrução no offset `pc`.
+///
+/// # Retorna
+/// A instrução e o offset da próxima, ou `None` se o bytecode estiver mal formado.
+pub fn decode(code: &[u8], pc: usize) -> Option<(Decoded, usize)> {
+    let opcode = Opcode::from_byte(*code.get(pc)?)?;
+    let operand = |size: usize| code.get(pc + 1..pc + 1 + size);
+
+    let (decoded, size) = match opcode {
+        Opcode::Push => (
+            Decoded::Push(i32::from_le_bytes(operand(4)?.try_into().ok()?)),
+            4,
+        ),
+        Opcode::Load => (
+            Decoded::Load(u16::from_le_bytes(operand(2)?.try_into().ok()?)),
+            2,
+        ),
+        Opcode::Store => (
+            Decoded::Store(u16::from_le_bytes(operand(2)?.try_into().ok()?)),
+            2,
+        ),
+        Opcode::Add | Opcode::Sub | Opcode::Mul | Opcode::Div => (Decoded::Arith(opcode), 0),
+        Opcode::Cmp => (Decoded::Cmp(relop_from_byte(operand(1)?[0])?), 1),
+        Opcode::Jump => (
+            Decoded::Jump(u32::from_le_bytes(operand(4)?.try_into().ok()?)),
+            4,
+        ),
+        Opcode::JumpIfFalse => (
+            Decoded::JumpIfFalse(u32::from_le_bytes(operand(4)?.try_into().ok()?)),
+            4,
+        ),
+        Opcode::Call => {
+            let bytes = operand(3)?;
+            (
+                Decoded::Call {
+                    function: u16::from_le_bytes([bytes[0], bytes[1]]),
+                    argc: bytes[2],
+                },
+                3,
+            )
+        }
+        Opcode::Ret => (Decoded::Ret, 0),
+        Opcode::Print => (Decoded::Print, 0),
+    };
+
+    Some((decoded, pc + 1 + size))
+}
+
+/// Lista o bytecode do módulo em formato legível, uma instrução por linha com o seu offset.
+pub fn disassemble(module: &Module) -> String {
+    let mut out = String::new();
+
+    for (index, function) in module.functions.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        writeln!(
+            out,
+            "função #{} {} (parâmetros: {}, slots: {}, {} bytes):",
+            index,
+            function.name,
+            function.arity,
+            function.slot_names.len(),
+            function.code.len()
+        )
+        .unwrap();
+
+        // Slots e funções inexistentes (em um módulo malformado) são listados como `???`
+        let slot = |slot: u16| {
+            function
+                .slot_names
+                .get(slot as usize)
+                .map_or("???", String::as_str)
+        };
+        let mut pc = 0;
+        while pc < function.code.len() {
+            let Some((decoded, next)) = decode(&function.code, pc) else {
+                writeln!(out, "  {:04x}  ??? {:#04x}", pc, function.code[pc]).unwrap();
+                break;
+            };
+            let text = match decoded {
+                Decoded::Push(value) => format!("PUSH {}", value),
+                Decoded::Load(index) => format!("LOAD {:<8} ; {}", index, slot(index)),
+                Decoded::Store(index) => format!("STORE {:<7} ; {}", index, slot(index)),
+                Decoded::Arith(opcode) => opcode.as_str().to_string(),
+                Decoded::Cmp(op) => format!("CMP {}", op.as_str()),
+                Decoded::Jump(target) => format!("JUMP {:04x}", target),
+                Decoded::JumpIfFalse(target) => format!("JUMP_IF_FALSE {:04x}", target),
+                Decoded::Call { function, argc } => format!(
+                    "CALL {:<8} ; {}, {} argumento(s)",
+                    function,
+                    module
+                        .functions
+                        .get(function as usize)
+                        .map_or("???", |function| function.name.as_str()),
+                    argc
+                ),
+                Decoded::Ret => "RET".to_string(),
+                Decoded::Print => "PRINT".to_string(),
+            };
+            writeln!(out, "  {:04x}  {}", pc, text).unwrap();
+            pc = next;
+        }
+    }
+
+    out
+}
@@ -0,0 +1,384 @@
+/// Tradução de programas LSI-2024-2 para C.
+///
+/// Cada `def` vira uma função C `int lsi_<nome>(int ...)` (o prefixo evita conflitos com
+/// funções da biblioteca C, como `printf` ou `main`), cada `int VARLIST` vira uma declaração
+/// com as variáveis iniciadas em 0, `:=` vira uma atribuição e `print` vira `printf`. Os blocos
+/// e os ramos do `if` são blocos C, de forma que as regras de escopo são as mesmas da linguagem.
+/// Variáveis com nomes reservados em C recebem o sufixo `_`, que não aparece em nomes da LSI-2024-2.
+///
+/// O código gerado segue a semântica do interpretador: as operações aritméticas passam por
+/// funções de suporte que encerram o programa em caso de divisão por zero ou estouro, as chamadas
+/// contam a profundidade de recursão até `MAX_CALL_DEPTH`, e o operando da esquerda de uma
+/// operação é guardado em um temporário quando os dois lados podem falhar, já que C não define
+/// a ordem de avaliação dos operandos e o erro relatado deve ser o mesmo do interpretador. Um programa formado por um único
+/// comando vira a função `principal`. O programa deve ter passado pela análise semântica.
+///
+/// O resultado pode ser compilado com qualquer compilador C99:
+/// ```text
+/// cc -o programa programa.c
+/// ```
+use std::fmt::Write;
+
+use crate::ast::{literal_value, BinOp, Expr, Ident, Program, Stmt};
+use crate::interpreter::{DEFAULT_ENTRY, MAX_CALL_DEPTH};
+
+/// Palavras reservadas de C, nomes definidos pelos cabeçalhos incluídos ou usados no corpo
+/// das funções geradas e macros predefinidas pelo GCC nos modos `gnu*`, que são o padrão do
+/// `cc`, que não podem ser usados como nomes de variáveis.
+const RESERVED: &[&str] = &[
+    "alignas",
+    "alignof",
+    "asm",
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "char",
+    "const",
+    "constexpr",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "nullptr",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "true",
+    "typedef",
+    "typeof",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+    "BUFSIZ",
+    "EOF",
+    "NULL",
+    "errno",
+    "i386",
+    "linux",
+    "main",
+    "printf",
+    "stderr",
+    "stdin",
+    "stdout",
+    "unix",
+];
+
+/// Gera o código C do programa, com uma função `main` que chama a função `entry`.
+///
+/// # Retorna
+/// - `Ok(String)`: O código C.
+/// - `Err(String)`: A função de entrada não existe ou recebe parâmetros.
+pub fn generate(program: &Program, entry: &str) -> Result<String, String> {
+    let mut functions: Vec<Function> = program
+        .functions
+        .iter()
+        .map(|function| Function {
+            name: &function.name.name,
+            params: &function.params,
+            body: &function.body,
+        })
+        .collect();
+    if let Some(statement) = &program.statement {
+        functions.push(Function {
+            name: DEFAULT_ENTRY,
+            params: &[],
+            body: std::slice::from_ref(statement),
+        });
+    }
+
+    match functions.iter().find(|function| function.name == entry) {
+        None => return Err(format!("a função de entrada `{}` não foi definida", entry)),
+        Some(function) if !function.params.is_empty() => {
+            return Err(format!(
+                "a função de entrada `{}` não pode receber parâmetros",
+                entry
+            ))
+        }
+        Some(_) => {}
+    }
+
+    let mut out = String::new();
+    out.push_str(&runtime());
+
+    out.push('\n');
+    for function in &functions {
+        writeln!(out, "{};", signature(function)).unwrap();
+    }
+    for function in &functions {
+        out.push('\n');
+        let mut generator = Generator {
+            out: &mut out,
+            indent: 0,
+            temps: 0,
+        };
+        generator.function(function);
+    }
+
+    write!(
+        out,
+        "\nint main(void) {{\n    (void)RUNTIME_CALL({}());\n    return 0;\n}}\n",
+        function_name(entry)
+    )
+    .unwrap();
+    Ok(out)
+}
+
+/// Cabeçalhos e funções de suporte do código gerado.
+fn runtime() -> String {
+    format!(
+        r#"#include <limits.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+static int runtime_depth; /* Número de chamadas em andamento */
+
+/* Imprime a mensagem na saída de erro e encerra o programa com código 1 */
+static void runtime_error(const char *message) {{
+    fprintf(stderr, "Erro de execução: %s\n", message);
+    exit(1);
+}}
+
+static void runtime_enter(void) {{
+    if (++runtime_depth > {depth}) {{
+        runtime_error("limite de {depth} chamadas aninhadas excedido");
+    }}
+}}
+
+static int runtime_leave(int value) {{
+    runtime_depth--;
+    return value;
+}}
+
+/* Chama uma função da LSI-2024-2 contando a profundidade de recursão */
+#define RUNTIME_CALL(call) (runtime_enter(), runtime_leave(call))
+
+static inline int runtime_check(long long value) {{
+    if (value < INT_MIN || value > INT_MAX) {{
+        runtime_error("estouro aritmético");
+    }}
+    return (int)value;
+}}
+
+static inline int runtime_add(int a, int b) {{ return runtime_check((long long)a + b); }}
+static inline int runtime_sub(int a, int b) {{ return runtime_check((long long)a - b); }}
+static inline int runtime_mul(int a, int b) {{ return runtime_check((long long)a * b); }}
+
+static inline int runtime_div(int a, int b) {{
+    if (b == 0) {{
+        runtime_error("divisão por zero");
+    }}
+    return runtime_check((long long)a / b);
+}}
+"#,
+        depth = MAX_CALL_DEPTH
+    )
+}
+
+/// Função a ser traduzida: um `def` ou o comando avulso, tratado como `principal`.
+struct Function<'a> {
+    name: &'a str,
+    params: &'a [Ident],
+    body: &'a [Stmt],
+}
+
+/// Nome C de uma função da LSI-2024-2.
+fn function_name(name: &str) -> String {
+    format!("lsi_{}", name)
+}
+
+/// Nome C de uma variável da LSI-2024-2.
+fn variable_name(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Protótipo de uma função: `int lsi_f(int a, int b)`.
+fn signature(function: &Function) -> String {
+    let params = match function.params.is_empty() {
+        true => "void".to_string(),
+        false => function
+            .params
+            .iter()
+            .map(|param| format!("int {}", variable_name(&param.name)))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    format!("int {}({})", function_name(function.name), params)
+}
+
+/// Estado da geração de uma função.
+struct Generator<'a> {
+    out: &'a mut String,
+    indent: usize, // Nível de indentação da próxima linha
+    temps: usize,  // Temporários declarados na função (`tmp_1`, `tmp_2`, ...)
+}
+
+impl Generator<'_> {
+    fn line(&mut self, line: &str) {
+        writeln!(self.out, "{}{}", "    ".repeat(self.indent), line).unwrap();
+    }
+
+    fn function(&mut self, function: &Function) {
+        self.line(&format!("{} {{", signature(function)));
+        self.indent += 1;
+        self.stmts(function.body);
+        if !matches!(function.body.last(), Some(Stmt::Return(_))) {
+            self.line("return 0;");
+        }
+        self.indent -= 1;
+        self.line("}");
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::VarDecl(names) => {
+                let names: Vec<String> = names
+                    .iter()
+                    .map(|name| format!("{} = 0", variable_name(&name.name)))
+                    .collect();
+                self.line(&format!("int {};", names.join(", ")));
+            }
+            Stmt::Assign { target, value } => {
+                let value = self.expr(value);
+                self.line(&format!("{} = {};", variable_name(&target.name), value));
+            }
+            Stmt::Print(expr) => {
+                let value = self.expr(expr);
+                self.line(&format!("printf(\"%d\\n\", {});", value));
+            }
+            Stmt::Return(Some(name)) => {
+                self.line(&format!("return {};", variable_name(&name.name)));
+            }
+            Stmt::Return(None) => self.line("return 0;"),
+            Stmt::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                // Expressões relacionais já são traduzidas entre parênteses
+                let cond = match cond {
+                    Expr::Relational { .. } => self.expr(cond),
+                    _ => format!("({})", self.expr(cond)),
+                };
+                self.line(&format!("if {} {{", cond));
+                self.branch(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.line("} else {");
+                    self.branch(else_branch);
+                }
+                self.line("}");
+            }
+            Stmt::Block(stmts) => {
+                self.line("{");
+                self.indent += 1;
+                self.stmts(stmts);
+                self.indent -= 1;
+                self.line("}");
+            }
+            Stmt::Empty => self.line(";"),
+        }
+    }
+
+    /// Ramo de um `if`, já entre chaves. Um bloco é escrito sem chaves extras, já que o
+    /// escopo do ramo não tem outras declarações.
+    fn branch(&mut self, stmt: &Stmt) {
+        self.indent += 1;
+        match stmt {
+            Stmt::Block(stmts) => self.stmts(stmts),
+            _ => self.stmt(stmt),
+        }
+        self.indent -= 1;
+    }
+
+    /// Traduz uma expressão, declarando antes do comando atual os temporários necessários
+    /// para manter a avaliação da esquerda para a direita.
+    fn expr(&mut self, expr: &Expr) -> String {
+        match expr {
+            Expr::Num { value, .. } => match literal_value(value) {
+                i32::MIN => "(-2147483647 - 1)".to_string(),
+                value if value < 0 => format!("({})", value),
+                value => value.to_string(),
+            },
+            Expr::Var(name) => variable_name(&name.name),
+            Expr::Binary { op, lhs, rhs } => {
+                let (lhs, rhs) = self.operands(lhs, rhs);
+                let helper = match op {
+                    BinOp::Add => "runtime_add",
+                    BinOp::Sub => "runtime_sub",
+                    BinOp::Mul => "runtime_mul",
+                    BinOp::Div => "runtime_div",
+                };
+                format!("{}({}, {})", helper, lhs, rhs)
+            }
+            Expr::Relational { op, lhs, rhs } => {
+                let (lhs, rhs) = self.operands(lhs, rhs);
+                let op = match op.as_str() {
+                    "<>" => "!=",
+                    op => op,
+                };
+                format!("({} {} {})", lhs, op, rhs)
+            }
+            Expr::Call(call) => {
+                let args: Vec<String> = call
+                    .args
+                    .iter()
+                    .map(|arg| variable_name(&arg.name))
+                    .collect();
+                format!(
+                    "RUNTIME_CALL({}({}))",
+                    function_name(&call.name.name),
+                    args.join(", ")
+                )
+            }
+        }
+    }
+
+    /// Traduz os operandos de uma operação. Se os dois lados não forem constantes nem
+    /// variáveis, o da esquerda é avaliado antes, em um temporário.
+    fn operands(&mut self, lhs: &Expr, rhs: &Expr) -> (String, String) {
+        let hoist = !is_simple(lhs) && !is_simple(rhs);
+        let mut lhs = self.expr(lhs);
+        if hoist {
+            self.temps += 1;
+            let temp = format!("tmp_{}", self.temps);
+            self.line(&format!("int {} = {};", temp, lhs));
+            lhs = temp;
+        }
+        let rhs = self.expr(rhs);
+        (lhs, rhs)
+    }
+}
+
+/// Indica se a expressão é uma constante ou variável, que não pode falhar.
+fn is_simple(expr: &Expr) -> bool {
+    matches!(expr, Expr::Num { .. } | Expr::Var(_))
+}
@@ -0,0 +1,659 @@
+/// Gerador de analisadores léxicos: compila as expressões regulares dos tokens em um autômato
+/// finito determinístico (AFD) mínimo, representado por uma tabela de transição.
+///
+/// A construção segue as etapas clássicas:
+/// 1. Cada expressão regular vira um autômato finito não determinístico (AFN) pela construção
+///    de Thompson, e os AFNs das regras são unidos por um novo estado inicial.
+/// 2. A construção de subconjuntos gera o AFD equivalente. Um estado do AFD aceita a regra de
+///    menor índice entre os estados de aceitação do AFN que ele contém, de forma que a regra
+///    declarada primeiro tem prioridade entre lexemas de mesmo tamanho.
+/// 3. O AFD é minimizado por refinamento de partições (algoritmo de Moore), a partir da
+///    partição dos estados pela regra aceita.
+///
+/// O alfabeto (todos os caracteres Unicode) é dividido em classes de caracteres que nenhuma
+/// expressão distingue, e a tabela tem uma coluna por classe.
+///
+/// Uma especificação léxica (`parse_spec`) tem uma regra por linha, com o nome e a expressão
+/// regular separados por espaços, em ordem de prioridade. Linhas vazias e iniciadas por `#`
+/// são ignoradas:
+///
+/// ```text
+/// # Tokens de uma linguagem de exemplo
+/// Id        [a-zA-Z][a-zA-Z0-9]*
+/// Int       [0-9]+
+/// Assign    :=
+/// ```
+///
+/// As expressões regulares aceitam:
+/// - `ab` (concatenação), `a|b` (alternativa), `a*`, `a+`, `a?` e parênteses;
+/// - `[abc]`, `[a-z]` e `[^...]` (classes de caracteres) e `.` (qualquer caractere exceto `\n`);
+/// - `\n`, `\t`, `\r`, `\s` (espaços em branco do Unicode, como `char::is_whitespace`),
+///   `\u{...}` (ponto de código em hexadecimal) e `\` seguido de qualquer outro caractere,
+///   que o representa literalmente (`\*`, `\|`, `\\`, ...).
+use std::collections::HashMap;
+
+use crate::diagnostic::Diagnostic;
+use crate::token::Span;
+
+/// Transição inexistente na tabela: nenhum lexema é aceito a partir daqui.
+pub const DEAD: u32 = u32::MAX;
+
+/// Maior ponto de código Unicode.
+const MAX_CHAR: u32 = char::MAX as u32;
+
+/// Caracteres com a propriedade White_Space do Unicode (os de `char::is_whitespace`), para `\s`.
+const WHITESPACE: &[(u32, u32)] = &[
+    (0x09, 0x0D),
+    (0x20, 0x20),
+    (0x85, 0x85),
+    (0xA0, 0xA0),
+    (0x1680, 0x1680),
+    (0x2000, 0x200A),
+    (0x2028, 0x2029),
+    (0x202F, 0x202F),
+    (0x205F, 0x205F),
+    (0x3000, 0x3000),
+];
+
+/// Regra de uma especificação léxica.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,    // Nome do token
+    pub pattern: String, // Expressão regular
+    pub span: Span,      // Posição da expressão regular na especificação
+}
+
+/// Erro em uma expressão regular.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexError {
+    pub rule: usize,     // Índice da regra
+    pub position: usize, // Posição do erro na expressão, em caracteres
+    pub message: String, // Descrição do erro
+}
+
+/// Lê uma especificação léxica.
+///
+/// # Retorna
+/// - `Ok(Vec<Rule>)`: As regras, em ordem de prioridade.
+/// - `Err(Vec<Diagnostic>)`: Linhas sem expressão regular e regras declaradas mais de uma vez.
+pub fn parse_spec(source: &str) -> Result<Vec<Rule>, Vec<Diagnostic>> {
+    let mut rules: Vec<Rule> = vec![];
+    let mut diagnostics = vec![];
+    let mut offset = 0;
+
+    for (index, line) in source.split('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len() + 1;
+        let text = line.trim_end();
+        let name = text.split_whitespace().next().unwrap_or("");
+        if name.is_empty() || name.starts_with('#') {
+            continue;
+        }
+
+        let name_start = text.len() - text.trim_start().len();
+        let after_name = name_start + name.len();
+        let pattern_start =
+            after_name + (text[after_name..].len() - text[after_name..].trim_start().len());
+        let span = |start: usize, end: usize| Span {
+            start: line_start + start,
+            end: line_start + end,
+            line: index + 1,
+            column: text[..start].chars().count() + 1,
+        };
+
+        if pattern_start == text.len() {
+            diagnostics.push(
+                Diagnostic::error(
+                    format!("a regra `{}` não tem uma expressão regular", name),
+                    span(name_start, after_name),
+                )
+                .with_label("esperado o nome e a expressão regular"),
+            );
+        } else if let Some(first) = rules.iter().find(|rule| rule.name == name) {
+            diagnostics.push(
+                Diagnostic::error(
+                    format!("a regra `{}` foi declarada mais de uma vez", name),
+                    span(name_start, after_name),
+                )
+                .with_label("declarada novamente aqui")
+                .with_secondary_label(first.span, "primeira declaração"),
+            );
+        } else {
+            rules.push(Rule {
+                name: name.to_string(),
+                pattern: text[pattern_start..].to_string(),
+                span: span(pattern_start, text.len()),
+            });
+        }
+    }
+
+    if diagnostics.is_empty() {
+        Ok(rules)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+/// Compila as regras de uma especificação em um AFD mínimo.
+///
+/// # Retorna
+/// - `Ok(Dfa)`: O autômato; a regra aceita em cada estado é o índice em `rules`.
+/// - `Err(Vec<Diagnostic>)`: O erro da primeira expressão regular inválida, na sua posição.
+pub fn compile(rules: &[Rule]) -> Result<Dfa, Vec<Diagnostic>> {
+    let patterns: Vec<&str> = rules.iter().map(|rule| rule.pattern.as_str()).collect();
+    Dfa::build(&patterns).map_err(|error| {
+        let rule = &rules[error.rule];
+        let skipped: usize = rule
+            .pattern
+            .chars()
+            .take(error.position)
+            .map(char::len_utf8)
+            .sum();
+        let start = rule.span.start + skipped;
+        let width = rule
+            .pattern
+            .chars()
+            .nth(error.position)
+            .map_or(0, char::len_utf8);
+        let span = Span {
+            start,
+            end: start + width,
+            line: rule.span.line,
+            column: rule.span.column + error.position,
+        };
+        vec![Diagnostic::error(
+            format!(
+                "expressão regular inválida na regra `{}`: {}",
+                rule.name, error.message
+            ),
+            span,
+        )
+        .with_label(error.message)]
+    })
+}
+
+/// AFD mínimo em forma de tabela. O estado inicial é o 0.
+#[derive(Debug, Clone)]
+pub struct Dfa {
+    boundaries: Vec<u32>,  // Primeiro ponto de código de cada classe de caracteres
+    transitions: Vec<u32>, // Próximo estado (ou `DEAD`) em `estado * classes + classe`
+    accept: Vec<Option<usize>>, // Regra aceita por cada estado
+}
+
+impl Dfa {
+    /// Compila as expressões regulares `patterns`, em ordem de prioridade, em um AFD mínimo.
+    /// Expressões que aceitam a cadeia vazia são rejeitadas, já que não formam um lexema.
+    pub fn build(patterns: &[&str]) -> Result<Dfa, RegexError> {
+        let mut nfa = Nfa { states: vec![] };
+        let start = nfa.state();
+        for (rule, pattern) in patterns.iter().enumerate() {
+            let regex = RegexParser::new(pattern)
+                .parse()
+                .map_err(|(position, message)| RegexError {
+                    rule,
+                    position,
+                    message,
+                })?;
+            let (first, last) = nfa.fragment(&regex);
+            nfa.states[start].epsilon.push(first);
+            nfa.states[last].accept = Some(rule);
+        }
+
+        let dfa = subsets(&nfa, start);
+        if let Some(rule) = dfa.accept[0] {
+            return Err(RegexError {
+                rule,
+                position: 0,
+                message: "a expressão regular aceita a cadeia vazia".to_string(),
+            });
+        }
+        Ok(dfa.minimize())
+    }
+
+    /// Número de estados.
+    pub fn states(&self) -> usize {
+        self.accept.len()
+    }
+
+    /// Número de classes de caracteres (colunas da tabela).
+    pub fn classes(&self) -> usize {
+        self.boundaries.len()
+    }
+
+    /// Regra aceita pelo estado `state`, se houver.
+    pub fn accepts(&self, state: u32) -> Option<usize> {
+        self.accept[state as usize]
+    }
+
+    /// Estado alcançado a partir de `state` com o caractere `ch`, ou `DEAD`.
+    pub fn next(&self, state: u32, ch: char) -> u32 {
+        self.transitions[state as usize * self.classes() + self.class(ch as u32)]
+    }
+
+    /// Maior prefixo de `input` aceito pelo autômato, em uma única passada: a leitura para
+    /// na primeira transição inexistente e volta ao último estado de aceitação.
+    ///
+    /// # Retorna
+    /// A regra aceita e o tamanho do lexema em bytes, ou `None` se nenhum prefixo for aceito.
+    pub fn longest_match(&self, input: &str) -> Option<(usize, usize)> {
+        let mut state = 0;
+        let mut accepted = None;
+        for (offset, ch) in input.char_indices() {
+            state = self.next(state, ch);
+            if state == DEAD {
+                break;
+            }
+            if let Some(rule) = self.accepts(state) {
+                accepted = Some((rule, offset + ch.len_utf8()));
+            }
+        }
+        accepted
+    }
+
+    /// Classe de caracteres do ponto de código `code`.
+    fn class(&self, code: u32) -> usize {
+        self.boundaries
+            .partition_point(|&boundary| boundary <= code)
+            - 1
+    }
+
+    /// Funde os estados equivalentes. Começando pela partição dos estados pela regra aceita,
+    /// cada bloco é dividido pelos blocos alcançados por cada classe de caracteres, até que
+    /// nenhum bloco mude. Os blocos são numerados pelo primeiro estado, e o inicial continua 0.
+    fn minimize(&self) -> Dfa {
+        let classes = self.classes();
+        let mut block = numbered(self.accept.iter());
+        loop {
+            let signatures = (0..self.states()).map(|state| {
+                let targets = self.transitions[state * classes..(state + 1) * classes]
+                    .iter()
+                    .map(|&target| match target {
+                        DEAD => usize::MAX,
+                        target => block[target as usize],
+                    });
+                (block[state], targets.collect::<Vec<_>>())
+            });
+            let refined = numbered(signatures.collect::<Vec<_>>().iter());
+            let stable = refined.iter().max() == block.iter().max();
+            block = refined;
+            if stable {
+                break;
+            }
+        }
+
+        let count = block.iter().max().map_or(0, |max| max + 1);
+        let mut transitions = vec![DEAD; count * classes];
+        let mut accept = vec![None; count];
+        for state in 0..self.states() {
+            let target = block[state];
+            accept[target] = self.accept[state];
+            for class in 0..classes {
+                transitions[target * classes + class] =
+                    match self.transitions[state * classes + class] {
+                        DEAD => DEAD,
+                        next => block[next as usize] as u32,
+                    };
+            }
+        }
+        Dfa {
+            boundaries: self.boundaries.clone(),
+            transitions,
+            accept,
+        }
+    }
+}
+
+/// Numera os valores distintos na ordem em que aparecem.
+fn numbered<'a, T: Eq + std::hash::Hash + 'a>(values: impl Iterator<Item = &'a T>) -> Vec<usize> {
+    let mut ids: HashMap<&T, usize> = HashMap::new();
+    values
+        .map(|value| {
+            let next = ids.len();
+            *ids.entry(value).or_insert(next)
+        })
+        .collect()
+}
+
+/// Construção de subconjuntos: cada estado do AFD é o fecho-ε de um conjunto de estados do AFN.
+fn subsets(nfa: &Nfa, start: usize) -> Dfa {
+    // Classes de caracteres: os extremos de todos os intervalos das transições dividem o alfabeto
+    let mut boundaries = vec![0];
+    for state in &nfa.states {
+        for &((first, last), _) in &state.moves {
+            boundaries.push(first);
+            if last < MAX_CHAR {
+                boundaries.push(last + 1);
+            }
+        }
+    }
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    let classes = boundaries.len();
+
+    let mut sets = vec![nfa.closure(vec![start])];
+    let mut ids: HashMap<Vec<usize>, u32> = HashMap::from([(sets[0].clone(), 0)]);
+    let mut transitions = vec![];
+    let mut current = 0;
+    while current < sets.len() {
+        for &code in &boundaries {
+            let targets: Vec<usize> = sets[current]
+                .iter()
+                .flat_map(|&state| &nfa.states[state].moves)
+                .filter(|((first, last), _)| (*first..=*last).contains(&code))
+                .map(|&(_, target)| target)
+                .collect();
+            if targets.is_empty() {
+                transitions.push(DEAD);
+                continue;
+            }
+            let set = nfa.closure(targets);
+            let id = *ids.entry(set.clone()).or_insert_with(|| {
+                sets.push(set);
+                sets.len() as u32 - 1
+            });
+            transitions.push(id);
+        }
+        current += 1;
+    }
+
+    let accept = sets
+        .iter()
+        .map(|set| {
+            set.iter()
+                .filter_map(|&state| nfa.states[state].accept)
+                .min()
+        })
+        .collect();
+    debug_assert_eq!(transitions.len(), sets.len() * classes);
+    Dfa {
+        boundaries,
+        transitions,
+        accept,
+    }
+}
+
+/// Autômato finito não determinístico.
+struct Nfa {
+    states: Vec<NfaState>,
+}
+
+/// Estado do AFN.
+#[derive(Default)]
+struct NfaState {
+    epsilon: Vec<usize>,             // Transições vazias
+    moves: Vec<((u32, u32), usize)>, // Intervalo de pontos de código -> próximo estado
+    accept: Option<usize>,           // Regra aceita neste estado
+}
+
+impl Nfa {
+    fn state(&mut self) -> usize {
+        self.states.push(NfaState::default());
+        self.states.len() - 1
+    }
+
+    /// Construção de Thompson: adiciona o fragmento que reconhece `regex`.
+    ///
+    /// # Retorna
+    /// Os estados inicial e final do fragmento.
+    fn fragment(&mut self, regex: &Regex) -> (usize, usize) {
+        match regex {
+            Regex::Empty => {
+                let state = self.state();
+                (state, state)
+            }
+            Regex::Set(ranges) => {
+                let (first, last) = (self.state(), self.state());
+                for &range in ranges {
+                    self.states[first].moves.push((range, last));
+                }
+                (first, last)
+            }
+            Regex::Concat(items) => {
+                let first = self.state();
+                let mut last = first;
+                for item in items {
+                    let (start, end) = self.fragment(item);
+                    self.states[last].epsilon.push(start);
+                    last = end;
+                }
+                (first, last)
+            }
+            Regex::Alt(items) => {
+                let (first, last) = (self.state(), self.state());
+                for item in items {
+                    let (start, end) = self.fragment(item);
+                    self.states[first].epsilon.push(start);
+                    self.states[end].epsilon.push(last);
+                }
+                (first, last)
+            }
+            Regex::Star(item) => {
+                let (first, last) = (self.state(), self.state());
+                let (start, end) = self.fragment(item);
+                self.states[first].epsilon.extend([start, last]);
+                self.states[end].epsilon.extend([start, last]);
+                (first, last)
+            }
+        }
+    }
+
+    /// Fecho-ε de `states`, ordenado e sem repetições.
+    fn closure(&self, mut pending: Vec<usize>) -> Vec<usize> {
+        let mut set = vec![false; self.states.len()];
+        while let Some(state) = pending.pop() {
+            if !set[state] {
+                set[state] = true;
+                pending.extend(&self.states[state].epsilon);
+            }
+        }
+        (0..set.len()).filter(|&state| set[state]).collect()
+    }
+}
+
+/// Expressão regular analisada.
+#[derive(Clone)]
+enum Regex {
+    Empty,                // Cadeia vazia
+    Set(Vec<(u32, u32)>), // Um caractere de um dos intervalos
+    Concat(Vec<Regex>),   // Sequência
+    Alt(Vec<Regex>),      // Alternativas
+    Star(Box<Regex>),     // Zero ou mais repetições
+}
+
+/// Analisador descendente recursivo das expressões regulares. Os erros são a posição,
+/// em caracteres, e a descrição.
+struct RegexParser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+type RegexResult<T> = Result<T, (usize, String)>;
+
+impl RegexParser {
+    fn new(pattern: &str) -> RegexParser {
+        RegexParser {
+            chars: pattern.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn parse(mut self) -> RegexResult<Regex> {
+        let regex = self.alternation()?;
+        match self.peek() {
+            Some(')') => Err(self.error("`)` sem o `(` correspondente")),
+            Some(_) => unreachable!("a alternativa consome a expressão até `)` ou o fim"),
+            None => Ok(regex),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.position += 1;
+        Some(ch)
+    }
+
+    fn error(&self, message: &str) -> (usize, String) {
+        (self.position, message.to_string())
+    }
+
+    // alternativa -> sequência ('|' sequência)*
+    fn alternation(&mut self) -> RegexResult<Regex> {
+        let mut items = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.next();
+            items.push(self.sequence()?);
+        }
+        Ok(match items.len() {
+            1 => items.pop().unwrap(),
+            _ => Regex::Alt(items),
+        })
+    }
+
+    // sequência -> repetição*
+    fn sequence(&mut self) -> RegexResult<Regex> {
+        let mut items = vec![];
+        while !matches!(self.peek(), None | Some('|') | Some(')')) {
+            items.push(self.repetition()?);
+        }
+        Ok(match items.len() {
+            0 => Regex::Empty,
+            1 => items.pop().unwrap(),
+            _ => Regex::Concat(items),
+        })
+    }
+
+    // repetição -> átomo ('*' | '+' | '?')*
+    fn repetition(&mut self) -> RegexResult<Regex> {
+        let mut regex = self.atom()?;
+        loop {
+            regex = match self.peek() {
+                Some('*') => Regex::Star(Box::new(regex)),
+                // `a+` = `a a*`: cada ocorrência vira um fragmento próprio do AFN
+                Some('+') => Regex::Concat(vec![regex.clone(), Regex::Star(Box::new(regex))]),
+                Some('?') => Regex::Alt(vec![regex, Regex::Empty]),
+                _ => return Ok(regex),
+            };
+            self.next();
+        }
+    }
+
+    // átomo -> '(' alternativa ')' | '[' classe ']' | '.' | '\' escape | caractere
+    fn atom(&mut self) -> RegexResult<Regex> {
+        let start = self.position;
+        match self.next() {
+            Some('(') => {
+                let regex = self.alternation()?;
+                if self.next() != Some(')') {
+                    return Err((start, "`(` sem o `)` correspondente".to_string()));
+                }
+                Ok(regex)
+            }
+            Some('[') => self.class(start),
+            Some('.') => Ok(Regex::Set(negate(&[('\n' as u32, '\n' as u32)]))),
+            Some('\\') => self.escape().map(Regex::Set),
+            Some(op @ ('*' | '+' | '?')) => Err((start, format!("`{}` sem operando", op))),
+            Some(ch) => Ok(Regex::Set(vec![(ch as u32, ch as u32)])),
+            None => unreachable!("a sequência termina no fim da expressão"),
+        }
+    }
+
+    // classe -> '^'? (caractere ('-' caractere)?)+
+    fn class(&mut self, start: usize) -> RegexResult<Regex> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.next();
+        }
+        let mut ranges = vec![];
+        loop {
+            let first = match self.peek() {
+                Some(']') if !ranges.is_empty() => {
+                    self.next();
+                    break;
+                }
+                Some(']') => return Err(self.error("classe de caracteres vazia")),
+                _ => self.class_item(start)?,
+            };
+            // `-` antes do `]` é literal
+            if self.peek() == Some('-') && self.chars.get(self.position + 1) != Some(&']') {
+                self.next();
+                let last = self.class_item(start)?;
+                match (first.as_slice(), last.as_slice()) {
+                    (&[(low, _)], &[(high, _)]) if low <= high => ranges.push((low, high)),
+                    _ => return Err(self.error("intervalo de caracteres inválido")),
+                }
+            } else {
+                ranges.extend(first);
+            }
+        }
+        let ranges = normalize(ranges);
+        Ok(Regex::Set(if negated { negate(&ranges) } else { ranges }))
+    }
+
+    /// Caractere (ou `\s`) de uma classe iniciada na posição `start`.
+    fn class_item(&mut self, start: usize) -> RegexResult<Vec<(u32, u32)>> {
+        match self.next() {
+            Some('\\') => self.escape(),
+            Some(ch) => Ok(vec![(ch as u32, ch as u32)]),
+            None => Err((start, "`[` sem o `]` correspondente".to_string())),
+        }
+    }
+
+    // escape -> 'n' | 't' | 'r' | 's' | 'u{' hexadecimal '}' | caractere
+    fn escape(&mut self) -> RegexResult<Vec<(u32, u32)>> {
+        let single = |ch: char| vec![(ch as u32, ch as u32)];
+        match self.next() {
+            Some('n') => Ok(single('\n')),
+            Some('t') => Ok(single('\t')),
+            Some('r') => Ok(single('\r')),
+            Some('s') => Ok(WHITESPACE.to_vec()),
+            Some('u') if self.peek() == Some('{') => {
+                self.next();
+                let digits: String = std::iter::from_fn(|| self.next())
+                    .take_while(|&ch| ch != '}')
+                    .collect();
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .map(single)
+                    .ok_or_else(|| self.error("ponto de código inválido em `\\u{...}`"))
+            }
+            Some(ch) => Ok(single(ch)),
+            None => Err(self.error("`\\` no fim da expressão")),
+        }
+    }
+}
+
+/// Ordena e funde intervalos sobrepostos ou adjacentes.
+fn normalize(mut ranges: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u32, u32)> = vec![];
+    for (first, last) in ranges {
+        match merged.last_mut() {
+            Some(previous) if first <= previous.1.saturating_add(1) => {
+                previous.1 = previous.1.max(last)
+            }
+            _ => merged.push((first, last)),
+        }
+    }
+    merged
+}
+
+/// Complemento de intervalos normalizados em relação a todos os pontos de código.
+fn negate(ranges: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut complement = vec![];
+    let mut next = 0;
+    for &(first, last) in ranges {
+        if first > next {
+            complement.push((next, first - 1));
+        }
+        next = last + 1;
+    }
+    if next <= MAX_CHAR {
+        complement.push((next, MAX_CHAR));
+    }
+    complement
+}
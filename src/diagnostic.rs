@@ -0,0 +1,355 @@
+/// Diagnósticos (erros e avisos) produzidos pelas etapas de análise.
+///
+/// Um diagnóstico tem uma mensagem principal e, opcionalmente, um código (`E0001`, `W0001`, ...),
+/// rótulos que destacam trechos do código fonte, notas e sugestões de correção. O `Display`
+/// produz uma única linha; `Renderer` mostra também as linhas do código fonte com os trechos
+/// sublinhados, como nos compiladores de linha de comando.
+use std::fmt;
+
+use crate::token::Span;
+
+/// Gravidade de um diagnóstico.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Papel de um rótulo: o primário marca o problema, os secundários dão contexto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelStyle {
+    Primary,   // Sublinhado com `^`
+    Secondary, // Sublinhado com `-`
+}
+
+/// Trecho do código fonte destacado por um diagnóstico, com uma explicação curta.
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub style: LabelStyle, // Papel do rótulo
+    pub span: Span,        // Trecho destacado
+    pub message: String,   // Texto exibido ao lado do sublinhado (pode ser vazio)
+}
+
+/// Correção sugerida: substituir o trecho `span` por `replacement`.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub message: String,     // Descrição da correção
+    pub span: Span,          // Trecho a ser substituído
+    pub replacement: String, // Texto que substitui o trecho
+}
+
+/// Diagnóstico associado a um trecho do código fonte.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,           // Gravidade do diagnóstico
+    pub code: Option<&'static str>,   // Código que identifica o tipo de problema
+    pub message: String,              // Descrição do problema
+    pub span: Span,                   // Trecho do código fonte onde o problema foi encontrado
+    pub labels: Vec<Label>,           // Trechos destacados na renderização
+    pub notes: Vec<String>,           // Informações adicionais
+    pub suggestions: Vec<Suggestion>, // Correções sugeridas
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Span) -> Diagnostic {
+        Diagnostic::new(Severity::Error, message.into(), span)
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Diagnostic {
+        Diagnostic::new(Severity::Warning, message.into(), span)
+    }
+
+    fn new(severity: Severity, message: String, span: Span) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code: None,
+            message,
+            span,
+            labels: vec![],
+            notes: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Define o código do diagnóstico.
+    pub fn with_code(mut self, code: &'static str) -> Diagnostic {
+        self.code = Some(code);
+        self
+    }
+
+    /// Adiciona o rótulo primário, no trecho do diagnóstico.
+    pub fn with_label(mut self, message: impl Into<String>) -> Diagnostic {
+        self.labels.push(Label {
+            style: LabelStyle::Primary,
+            span: self.span,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Adiciona um rótulo secundário em outro trecho do código fonte.
+    pub fn with_secondary_label(mut self, span: Span, message: impl Into<String>) -> Diagnostic {
+        self.labels.push(Label {
+            style: LabelStyle::Secondary,
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Diagnostic {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Sugere substituir o trecho `span` por `replacement`.
+    pub fn with_suggestion(
+        mut self,
+        message: impl Into<String>,
+        span: Span,
+        replacement: impl Into<String>,
+    ) -> Diagnostic {
+        self.suggestions.push(Suggestion {
+            message: message.into(),
+            span,
+            replacement: replacement.into(),
+        });
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self.severity {
+            Severity::Error => "Erro",
+            Severity::Warning => "Aviso",
+        };
+        write!(
+            f,
+            "{} na linha {}, coluna {}: {}",
+            label, self.span.line, self.span.column, self.message
+        )
+    }
+}
+
+// Estilos ANSI usados pelo `Renderer`
+const BOLD: &str = "1";
+const RED: &str = "1;31";
+const YELLOW: &str = "1;33";
+const BLUE: &str = "1;34";
+const GREEN: &str = "1;32";
+
+/// Largura de uma tabulação nas linhas exibidas.
+const TAB_WIDTH: usize = 4;
+
+/// Renderiza diagnósticos com as linhas do código fonte a que eles se referem:
+///
+/// ```text
+/// erro[E0003]: operador `!=` inválido; o operador diferente da LSI-2024-2 é `<>`
+///  --> programa.lsi:3:11
+///   |
+/// 3 |     if (a != b) print a;
+///   |           ^^ operador inválido
+///   |
+///   = sugestão: use `<>`
+///   |
+/// 3 |     if (a <> b) print a;
+///   |           ~~
+/// ```
+pub struct Renderer<'a> {
+    source: &'a str, // Código fonte a que os diagnósticos se referem
+    path: &'a str,   // Nome do arquivo exibido na localização
+    color: bool,     // Usa cores ANSI
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(source: &'a str, path: &'a str, color: bool) -> Renderer<'a> {
+        Renderer {
+            source,
+            path,
+            color,
+        }
+    }
+
+    /// Código fonte a que os diagnósticos se referem.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Renderiza o diagnóstico, terminando com uma quebra de linha.
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let (level, level_style) = match diagnostic.severity {
+            Severity::Error => ("erro", RED),
+            Severity::Warning => ("aviso", YELLOW),
+        };
+
+        // Sem rótulo primário explícito, o trecho do diagnóstico é sublinhado sem texto
+        let mut labels = diagnostic.labels.clone();
+        if !labels
+            .iter()
+            .any(|label| label.style == LabelStyle::Primary)
+        {
+            labels.insert(
+                0,
+                Label {
+                    style: LabelStyle::Primary,
+                    span: diagnostic.span,
+                    message: String::new(),
+                },
+            );
+        }
+        let mut lines: Vec<usize> = labels.iter().map(|label| label.span.line).collect();
+        lines.extend(diagnostic.suggestions.iter().map(|s| s.span.line));
+        let width = lines.iter().max().unwrap_or(&1).to_string().len();
+        lines.sort();
+        lines.dedup();
+
+        let mut out = String::new();
+        let header = match diagnostic.code {
+            Some(code) => format!("{}[{}]", level, code),
+            None => level.to_string(),
+        };
+        out.push_str(&self.paint(&header, level_style));
+        out.push_str(&self.paint(&format!(": {}", diagnostic.message), BOLD));
+        out.push('\n');
+        out.push_str(&format!(
+            "{}{} {}:{}:{}\n",
+            " ".repeat(width),
+            self.paint("-->", BLUE),
+            self.path,
+            diagnostic.span.line,
+            diagnostic.span.column
+        ));
+        out.push_str(&self.gutter(width, None));
+        out.push('\n');
+
+        let mut previous: Option<usize> = None;
+        for line in lines
+            .iter()
+            .filter(|&&line| labels.iter().any(|label| label.span.line == line))
+        {
+            // Linhas omitidas entre dois trechos destacados
+            if previous.is_some_and(|previous| *line > previous + 1) {
+                out.push_str(&self.paint("...", BLUE));
+                out.push('\n');
+            }
+            previous = Some(*line);
+            out.push_str(&self.gutter(width, Some(*line)));
+            out.push(' ');
+            out.push_str(&expand_tabs(self.line(*line)));
+            out.push('\n');
+            for label in labels.iter().filter(|label| label.span.line == *line) {
+                let (mark, style) = match label.style {
+                    LabelStyle::Primary => ('^', level_style),
+                    LabelStyle::Secondary => ('-', BLUE),
+                };
+                let (column, length) = self.underline(label.span);
+                let mut underline = mark.to_string().repeat(length);
+                if !label.message.is_empty() {
+                    underline = format!("{} {}", underline, label.message);
+                }
+                out.push_str(&self.gutter(width, None));
+                out.push_str(&" ".repeat(column + 1));
+                out.push_str(&self.paint(&underline, style));
+                out.push('\n');
+            }
+        }
+
+        if !diagnostic.notes.is_empty() || !diagnostic.suggestions.is_empty() {
+            out.push_str(&self.gutter(width, None));
+            out.push('\n');
+        }
+        for note in &diagnostic.notes {
+            out.push_str(&format!(
+                "{} {} {}\n",
+                " ".repeat(width),
+                self.paint("= nota:", BOLD),
+                note
+            ));
+        }
+        for suggestion in &diagnostic.suggestions {
+            out.push_str(&format!(
+                "{} {} {}\n",
+                " ".repeat(width),
+                self.paint("= sugestão:", BOLD),
+                suggestion.message
+            ));
+            out.push_str(&self.suggestion(suggestion, width));
+        }
+        out
+    }
+
+    /// Linha corrigida pela sugestão, com o texto inserido sublinhado.
+    fn suggestion(&self, suggestion: &Suggestion, width: usize) -> String {
+        let line = self.line(suggestion.span.line);
+        let skip = suggestion.span.column.saturating_sub(1);
+        let prefix: String = line.chars().take(skip).collect();
+        let removed = self.span_text(suggestion.span).chars().count();
+        let rest: String = line.chars().skip(skip + removed).collect();
+        let fixed = format!("{}{}{}", prefix, suggestion.replacement, rest);
+
+        let mut out = self.gutter(width, None);
+        out.push('\n');
+        out.push_str(&self.gutter(width, Some(suggestion.span.line)));
+        out.push(' ');
+        out.push_str(&expand_tabs(&fixed));
+        out.push('\n');
+        out.push_str(&self.gutter(width, None));
+        out.push_str(&" ".repeat(expand_tabs(&prefix).chars().count() + 1));
+        let length = expand_tabs(&suggestion.replacement).chars().count().max(1);
+        out.push_str(&self.paint(&"~".repeat(length), GREEN));
+        out.push('\n');
+        out
+    }
+
+    /// Coluna (já com as tabulações expandidas) e largura do sublinhado de um trecho.
+    /// Um trecho que se estende por várias linhas é sublinhado até o fim da primeira.
+    fn underline(&self, span: Span) -> (usize, usize) {
+        let line = self.line(span.line);
+        let prefix: String = line.chars().take(span.column.saturating_sub(1)).collect();
+        let text = self.span_text(span);
+        let text = text.split('\n').next().unwrap_or("");
+        (
+            expand_tabs(&prefix).chars().count(),
+            expand_tabs(text).chars().count().max(1),
+        )
+    }
+
+    /// Texto do trecho, ou vazio se o trecho não corresponder a offsets válidos do código fonte.
+    fn span_text(&self, span: Span) -> &str {
+        self.source.get(span.start..span.end).unwrap_or("")
+    }
+
+    /// Texto da linha `line` (a partir de 1), ou vazio se ela não existir.
+    fn line(&self, line: usize) -> &str {
+        self.source
+            .lines()
+            .nth(line.saturating_sub(1))
+            .unwrap_or("")
+    }
+
+    /// Margem com o número da linha, ou vazia.
+    fn gutter(&self, width: usize, line: Option<usize>) -> String {
+        let number = match line {
+            Some(line) => format!("{:>width$}", line, width = width),
+            None => " ".repeat(width),
+        };
+        self.paint(&format!("{} |", number), BLUE)
+    }
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", style, text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
@@ -0,0 +1,635 @@
+/// Gramáticas livres de contexto e construção de tabelas LL(1).
+///
+/// Uma gramática é descrita apenas pelas suas produções: símbolos que aparecem do lado esquerdo
+/// de alguma produção são não terminais e todos os demais são terminais. A partir das produções
+/// são calculados os conjuntos FIRST e FOLLOW e a tabela de análise preditiva.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Símbolo que representa a cadeia vazia.
+pub const EPSILON: &str = "ε";
+/// Símbolo que representa o fim da entrada.
+pub const END_MARKER: &str = "$";
+
+/// Produção `lhs -> rhs`. Um lado direito vazio representa ε.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Production {
+    pub lhs: String,
+    pub rhs: Vec<String>,
+}
+
+impl std::fmt::Display for Production {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.rhs.is_empty() {
+            write!(f, "{} -> {}", self.lhs, EPSILON)
+        } else {
+            write!(f, "{} -> {}", self.lhs, self.rhs.join(" "))
+        }
+    }
+}
+
+/// Preferência declarada para uma entrada em conflito: em `M[nonterminal, terminal]`
+/// aplica-se sempre a produção `production`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preference {
+    pub nonterminal: String,
+    pub terminal: String,
+    pub production: usize, // Índice em `Grammar::productions`
+}
+
+/// Regra de lookahead estendido: em `M[nonterminal, tokens[0]]`, se os próximos tokens
+/// da entrada forem exatamente `tokens`, aplica-se a produção `production`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lookahead {
+    pub nonterminal: String,
+    pub tokens: Vec<String>,
+    pub production: usize, // Índice em `Grammar::productions`
+}
+
+/// Gramática livre de contexto.
+#[derive(Debug, Clone)]
+pub struct Grammar {
+    pub start: String,                         // Símbolo inicial
+    pub productions: Vec<Production>,          // Produções na ordem em que foram declaradas
+    pub preferences: Vec<Preference>,          // Preferências declaradas para entradas em conflito
+    pub lookaheads: Vec<Lookahead>,            // Regras que usam mais de um token de lookahead
+    pub descriptions: HashMap<String, String>, // Contexto de cada não terminal nas mensagens de erro
+}
+
+impl Grammar {
+    pub fn new(start: &str) -> Grammar {
+        Grammar {
+            start: start.to_string(),
+            productions: vec![],
+            preferences: vec![],
+            lookaheads: vec![],
+            descriptions: HashMap::new(),
+        }
+    }
+
+    /// Índice da produção `nonterminal -> alternative`, se ela existir.
+    pub fn find_production(&self, nonterminal: &str, alternative: &str) -> Option<usize> {
+        let rhs: Vec<&str> = alternative
+            .split_whitespace()
+            .filter(|symbol| *symbol != EPSILON)
+            .collect();
+        self.productions_of(nonterminal)
+            .find(|(_, production)| production.rhs == rhs)
+            .map(|(index, _)| index)
+    }
+
+    /// Declara que, em `M[nonterminal, terminal]`, a alternativa `alternative` deve ser aplicada.
+    ///
+    /// # Pânico
+    /// Entra em pânico se a produção `nonterminal -> alternative` não tiver sido declarada.
+    pub fn prefer(&mut self, nonterminal: &str, terminal: &str, alternative: &str) {
+        let production = self
+            .find_production(nonterminal, alternative)
+            .unwrap_or_else(|| {
+                panic!(
+                    "produção `{} -> {}` não declarada",
+                    nonterminal, alternative
+                )
+            });
+        self.preferences.push(Preference {
+            nonterminal: nonterminal.to_string(),
+            terminal: terminal.to_string(),
+            production,
+        });
+    }
+
+    /// Declara que, quando os próximos tokens forem `tokens`, a alternativa `alternative`
+    /// deve ser aplicada para `nonterminal`, independentemente da entrada da tabela.
+    ///
+    /// # Pânico
+    /// Entra em pânico se a produção `nonterminal -> alternative` não tiver sido declarada
+    /// ou se `tokens` for vazio.
+    pub fn lookahead(&mut self, nonterminal: &str, tokens: &[&str], alternative: &str) {
+        assert!(!tokens.is_empty(), "lookahead vazio para `{}`", nonterminal);
+        let production = self
+            .find_production(nonterminal, alternative)
+            .unwrap_or_else(|| {
+                panic!(
+                    "produção `{} -> {}` não declarada",
+                    nonterminal, alternative
+                )
+            });
+        self.lookaheads.push(Lookahead {
+            nonterminal: nonterminal.to_string(),
+            tokens: tokens.iter().map(|token| token.to_string()).collect(),
+            production,
+        });
+    }
+
+    /// Declara como os erros sintáticos em `nonterminal` são situados nas mensagens,
+    /// por exemplo `após a lista de variáveis`.
+    pub fn describe(&mut self, nonterminal: &str, description: &str) {
+        self.descriptions
+            .insert(nonterminal.to_string(), description.to_string());
+    }
+
+    /// Adiciona as alternativas de um não terminal.
+    /// Cada alternativa é uma sequência de símbolos separados por espaço, ou `ε`.
+    pub fn add(&mut self, lhs: &str, alternatives: &[&str]) {
+        for alternative in alternatives {
+            let rhs = alternative
+                .split_whitespace()
+                .filter(|symbol| *symbol != EPSILON)
+                .map(str::to_string)
+                .collect();
+            self.productions.push(Production {
+                lhs: lhs.to_string(),
+                rhs,
+            });
+        }
+    }
+
+    /// Não terminais na ordem em que aparecem pela primeira vez.
+    pub fn nonterminals(&self) -> Vec<&str> {
+        let mut nonterminals: Vec<&str> = vec![];
+        for production in &self.productions {
+            if !nonterminals.contains(&production.lhs.as_str()) {
+                nonterminals.push(&production.lhs);
+            }
+        }
+        nonterminals
+    }
+
+    pub fn is_nonterminal(&self, symbol: &str) -> bool {
+        self.productions
+            .iter()
+            .any(|production| production.lhs == symbol)
+    }
+
+    /// Produções de um não terminal, junto com os seus índices em `productions`.
+    pub fn productions_of<'a>(
+        &'a self,
+        nonterminal: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a Production)> + 'a {
+        self.productions
+            .iter()
+            .enumerate()
+            .filter(move |(_, production)| production.lhs == nonterminal)
+    }
+
+    /// Calcula o conjunto FIRST de cada não terminal.
+    /// O conjunto contém `ε` quando o não terminal deriva a cadeia vazia.
+    pub fn first_sets(&self) -> HashMap<String, BTreeSet<String>> {
+        let mut first: HashMap<String, BTreeSet<String>> = self
+            .nonterminals()
+            .into_iter()
+            .map(|nonterminal| (nonterminal.to_string(), BTreeSet::new()))
+            .collect();
+
+        // Itera até que nenhum conjunto mude (ponto fixo)
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                let symbols = self.first_of(&production.rhs, &first);
+                let set = first.get_mut(&production.lhs).unwrap();
+                for symbol in symbols {
+                    changed |= set.insert(symbol);
+                }
+            }
+        }
+
+        first
+    }
+
+    /// Calcula o conjunto FIRST de uma sequência de símbolos a partir dos conjuntos FIRST
+    /// dos não terminais. O resultado contém `ε` se toda a sequência deriva a cadeia vazia.
+    pub fn first_of(
+        &self,
+        symbols: &[String],
+        first: &HashMap<String, BTreeSet<String>>,
+    ) -> BTreeSet<String> {
+        let mut result = BTreeSet::new();
+
+        for symbol in symbols {
+            match first.get(symbol) {
+                Some(set) => {
+                    result.extend(set.iter().filter(|s| *s != EPSILON).cloned());
+                    if !set.contains(EPSILON) {
+                        return result;
+                    }
+                }
+                None => {
+                    result.insert(symbol.clone());
+                    return result;
+                }
+            }
+        }
+
+        result.insert(EPSILON.to_string());
+        result
+    }
+
+    /// Calcula o conjunto FOLLOW de cada não terminal.
+    /// O FOLLOW do símbolo inicial contém o marcador de fim de entrada `$`.
+    pub fn follow_sets(
+        &self,
+        first: &HashMap<String, BTreeSet<String>>,
+    ) -> HashMap<String, BTreeSet<String>> {
+        let mut follow: HashMap<String, BTreeSet<String>> = self
+            .nonterminals()
+            .into_iter()
+            .map(|nonterminal| (nonterminal.to_string(), BTreeSet::new()))
+            .collect();
+        if let Some(set) = follow.get_mut(&self.start) {
+            set.insert(END_MARKER.to_string());
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                for (i, symbol) in production.rhs.iter().enumerate() {
+                    if !self.is_nonterminal(symbol) {
+                        continue;
+                    }
+
+                    // FOLLOW(B) inclui FIRST(β) para A -> α B β,
+                    // e FOLLOW(A) quando β deriva ε
+                    let rest = self.first_of(&production.rhs[i + 1..], first);
+                    let mut additions: Vec<String> =
+                        rest.iter().filter(|s| *s != EPSILON).cloned().collect();
+                    if rest.contains(EPSILON) {
+                        additions.extend(follow[&production.lhs].iter().cloned());
+                    }
+
+                    let set = follow.get_mut(symbol).unwrap();
+                    for terminal in additions {
+                        changed |= set.insert(terminal);
+                    }
+                }
+            }
+        }
+
+        follow
+    }
+
+    /// Constrói a tabela de análise LL(1) da gramática.
+    ///
+    /// Para cada produção `A -> α`, a produção é associada a cada terminal de FIRST(α) e,
+    /// se α deriva ε, a cada terminal de FOLLOW(A).
+    ///
+    /// # Política de resolução de conflitos
+    /// Se mais de uma produção disputar a mesma entrada, a gramática não é LL(1). Cada disputa
+    /// é registrada em `ParseTable::conflicts` e resolvida, em ordem de prioridade:
+    /// 1. por regras de lookahead estendido (`Grammar::lookahead`) que partem dessa entrada,
+    ///    consultadas antes da produção padrão da entrada;
+    /// 2. por uma preferência declarada (`Grammar::prefer`), que define a produção padrão;
+    /// 3. pela ordem de declaração: a produção declarada primeiro é a padrão.
+    pub fn ll1_table(&self) -> ParseTable {
+        let first = self.first_sets();
+        let follow = self.follow_sets(&first);
+
+        // Candidatas de cada entrada, indicando se vieram de FIRST(α) ou de FOLLOW(A)
+        let mut candidates: BTreeMap<(String, String), Vec<(usize, ConflictSource)>> =
+            BTreeMap::new();
+        for (index, production) in self.productions.iter().enumerate() {
+            let first_alpha = self.first_of(&production.rhs, &first);
+
+            for terminal in first_alpha.iter().filter(|s| *s != EPSILON) {
+                candidates
+                    .entry((production.lhs.clone(), terminal.clone()))
+                    .or_default()
+                    .push((index, ConflictSource::First));
+            }
+            if first_alpha.contains(EPSILON) {
+                for terminal in &follow[&production.lhs] {
+                    candidates
+                        .entry((production.lhs.clone(), terminal.clone()))
+                        .or_default()
+                        .push((index, ConflictSource::Follow));
+                }
+            }
+        }
+
+        let mut entries: HashMap<String, BTreeMap<String, Entry>> = HashMap::new();
+        let mut conflicts = vec![];
+        for ((nonterminal, terminal), mut options) in candidates {
+            options.dedup_by_key(|(index, _)| *index);
+
+            let preference = self.preferences.iter().find(|preference| {
+                preference.nonterminal == nonterminal
+                    && preference.terminal == terminal
+                    && options
+                        .iter()
+                        .any(|(index, _)| *index == preference.production)
+            });
+            let lookaheads: Vec<Lookahead> = self
+                .lookaheads
+                .iter()
+                .filter(|rule| rule.nonterminal == nonterminal && rule.tokens[0] == terminal)
+                .cloned()
+                .collect();
+            let chosen = preference.map_or(options[0].0, |preference| preference.production);
+
+            if options.len() > 1 {
+                let from_first = options.iter().any(|(_, s)| *s == ConflictSource::First);
+                let from_follow = options.iter().any(|(_, s)| *s == ConflictSource::Follow);
+                // A produção escolhida como padrão é listada primeiro
+                options.sort_by_key(|(index, _)| *index != chosen);
+                conflicts.push(Conflict {
+                    kind: if from_first && from_follow {
+                        ConflictKind::FirstFollow
+                    } else {
+                        ConflictKind::FirstFirst
+                    },
+                    nonterminal: nonterminal.clone(),
+                    terminal: terminal.clone(),
+                    productions: options
+                        .iter()
+                        .map(|(index, _)| self.productions[*index].clone())
+                        .collect(),
+                    resolution: if !lookaheads.is_empty() {
+                        Resolution::Lookahead(
+                            lookaheads
+                                .iter()
+                                .map(|rule| {
+                                    (
+                                        rule.tokens.clone(),
+                                        self.productions[rule.production].clone(),
+                                    )
+                                })
+                                .collect(),
+                        )
+                    } else if preference.is_some() {
+                        Resolution::Preference
+                    } else {
+                        Resolution::DeclarationOrder
+                    },
+                });
+            }
+
+            entries.entry(nonterminal).or_default().insert(
+                terminal,
+                Entry {
+                    production: chosen,
+                    lookaheads: lookaheads
+                        .into_iter()
+                        .map(|rule| (rule.tokens, rule.production))
+                        .collect(),
+                },
+            );
+        }
+
+        // Reporta os conflitos na ordem em que os não terminais foram declarados
+        let order = self.nonterminals();
+        conflicts.sort_by_key(|conflict| {
+            order
+                .iter()
+                .position(|nonterminal| *nonterminal == conflict.nonterminal)
+        });
+
+        ParseTable {
+            grammar: self.clone(),
+            follow,
+            entries,
+            conflicts,
+        }
+    }
+
+    /// Analisa a gramática e retorna todos os conflitos LL(1) encontrados.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        self.ll1_table().conflicts
+    }
+
+    /// Não terminais que não derivam nenhuma cadeia de terminais.
+    pub fn nonproductive(&self) -> Vec<&str> {
+        let mut productive: BTreeSet<&str> = BTreeSet::new();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                let derives = production.rhs.iter().all(|symbol| {
+                    !self.is_nonterminal(symbol) || productive.contains(symbol.as_str())
+                });
+                if derives {
+                    changed |= productive.insert(&production.lhs);
+                }
+            }
+        }
+
+        self.nonterminals()
+            .into_iter()
+            .filter(|nonterminal| !productive.contains(nonterminal))
+            .collect()
+    }
+
+    /// Não terminais que não podem ser alcançados a partir do símbolo inicial.
+    pub fn unreachable(&self) -> Vec<&str> {
+        let mut reachable: BTreeSet<&str> = BTreeSet::from([self.start.as_str()]);
+        let mut pending = vec![self.start.as_str()];
+
+        while let Some(nonterminal) = pending.pop() {
+            for (_, production) in self.productions_of(nonterminal) {
+                for symbol in &production.rhs {
+                    if self.is_nonterminal(symbol) && reachable.insert(symbol) {
+                        pending.push(symbol);
+                    }
+                }
+            }
+        }
+
+        self.nonterminals()
+            .into_iter()
+            .filter(|nonterminal| !reachable.contains(nonterminal))
+            .collect()
+    }
+
+    /// Não terminais com recursão à esquerda (direta ou indireta), que um parser
+    /// preditivo não consegue analisar.
+    pub fn left_recursive(&self) -> Vec<&str> {
+        let first = self.first_sets();
+        let nullable = |symbol: &String| first.get(symbol).is_some_and(|set| set.contains(EPSILON));
+
+        // A -> B se existe A -> α B β com α derivando ε
+        let mut edges: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for production in &self.productions {
+            for symbol in &production.rhs {
+                if !self.is_nonterminal(symbol) {
+                    break;
+                }
+                edges.entry(&production.lhs).or_default().insert(symbol);
+                if !nullable(symbol) {
+                    break;
+                }
+            }
+        }
+
+        // Um não terminal é recursivo à esquerda se alcança a si mesmo no grafo
+        self.nonterminals()
+            .into_iter()
+            .filter(|&nonterminal| {
+                let mut visited: BTreeSet<&str> = BTreeSet::new();
+                let mut pending: Vec<&str> = edges
+                    .get(nonterminal)
+                    .map(|next| next.iter().copied().collect())
+                    .unwrap_or_default();
+                while let Some(current) = pending.pop() {
+                    if current == nonterminal {
+                        return true;
+                    }
+                    if visited.insert(current) {
+                        if let Some(next) = edges.get(current) {
+                            pending.extend(next.iter().copied());
+                        }
+                    }
+                }
+                false
+            })
+            .collect()
+    }
+}
+
+/// Origem de uma candidata a uma entrada da tabela.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConflictSource {
+    First,  // O terminal pertence a FIRST(α)
+    Follow, // α deriva ε e o terminal pertence a FOLLOW(A)
+}
+
+/// Tipo de conflito LL(1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    FirstFirst,  // Duas alternativas começam com o mesmo terminal (ou ambas derivam ε)
+    FirstFollow, // Uma alternativa deriva ε e o terminal também inicia outra alternativa
+}
+
+/// Como um conflito LL(1) é resolvido na tabela.
+#[derive(Debug, Clone)]
+pub enum Resolution {
+    DeclarationOrder, // Prevalece a alternativa declarada primeiro
+    Preference,       // Preferência declarada com `Grammar::prefer`
+    Lookahead(Vec<(Vec<String>, Production)>), // Regras declaradas com `Grammar::lookahead`
+}
+
+/// Conflito LL(1): mais de uma produção para a mesma entrada `M[nonterminal, terminal]`.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    pub nonterminal: String,
+    pub terminal: String,
+    pub productions: Vec<Production>, // Produções envolvidas; a primeira é a padrão
+    pub resolution: Resolution,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let kind = match self.kind {
+            ConflictKind::FirstFirst => "FIRST/FIRST",
+            ConflictKind::FirstFollow => "FIRST/FOLLOW",
+        };
+        let resolution = match &self.resolution {
+            Resolution::DeclarationOrder => "resolvido pela ordem de declaração".to_string(),
+            Resolution::Preference => "resolvido por preferência declarada".to_string(),
+            Resolution::Lookahead(rules) => {
+                let depth = rules
+                    .iter()
+                    .map(|(tokens, _)| tokens.len())
+                    .max()
+                    .unwrap_or(1);
+                format!("resolvido com {} tokens de lookahead", depth)
+            }
+        };
+        write!(
+            f,
+            "Conflito {} em M[{}, {}] ({}):",
+            kind, self.nonterminal, self.terminal, resolution
+        )?;
+        for (i, production) in self.productions.iter().enumerate() {
+            let note = if i == 0 { "  (padrão)" } else { "" };
+            write!(f, "\n    {}{}", production, note)?;
+        }
+        if let Resolution::Lookahead(rules) = &self.resolution {
+            for (tokens, production) in rules {
+                write!(
+                    f,
+                    "\n    com `{}` na entrada: {}",
+                    tokens.join(" "),
+                    production
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Tabela de análise preditiva gerada a partir de uma gramática.
+#[derive(Debug, Clone)]
+pub struct ParseTable {
+    pub grammar: Grammar,
+    pub follow: HashMap<String, BTreeSet<String>>,
+    pub conflicts: Vec<Conflict>, // Entradas disputadas por mais de uma produção
+    entries: HashMap<String, BTreeMap<String, Entry>>, // não terminal -> terminal -> entrada
+}
+
+/// Entrada da tabela: a produção padrão e as regras de lookahead estendido que partem dela.
+#[derive(Debug, Clone)]
+struct Entry {
+    production: usize,
+    lookaheads: Vec<(Vec<String>, usize)>,
+}
+
+impl ParseTable {
+    /// Produção padrão para o não terminal `nonterminal` com o terminal `terminal` na entrada.
+    pub fn get(&self, nonterminal: &str, terminal: &str) -> Option<&Production> {
+        self.entries
+            .get(nonterminal)?
+            .get(terminal)
+            .map(|entry| &self.grammar.productions[entry.production])
+    }
+
+    /// Produção a ser aplicada para `nonterminal` dados os próximos terminais da entrada.
+    ///
+    /// `lookahead` deve conter ao menos `lookahead_depth()` terminais (ou terminar em `$`).
+    /// Regras de lookahead estendido têm prioridade sobre a produção padrão da entrada.
+    pub fn select(&self, nonterminal: &str, lookahead: &[&str]) -> Option<&Production> {
+        let entry = self.entries.get(nonterminal)?.get(*lookahead.first()?)?;
+        let index = entry
+            .lookaheads
+            .iter()
+            .find(|(tokens, _)| {
+                tokens.len() <= lookahead.len() && tokens.iter().zip(lookahead).all(|(a, b)| a == b)
+            })
+            .map_or(entry.production, |(_, index)| *index);
+        Some(&self.grammar.productions[index])
+    }
+
+    /// Quantidade máxima de tokens de lookahead usada pela tabela.
+    pub fn lookahead_depth(&self) -> usize {
+        self.grammar
+            .lookaheads
+            .iter()
+            .map(|rule| rule.tokens.len())
+            .max()
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    /// Terminais com uma entrada na tabela para `nonterminal`, isto é, os terminais aceitos
+    /// quando `nonterminal` está no topo da pilha. O fim da entrada (`$`) vem por último.
+    pub fn expected(&self, nonterminal: &str) -> Vec<&str> {
+        let mut expected: Vec<&str> = self
+            .entries
+            .get(nonterminal)
+            .map(|entries| entries.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        if let Some(end) = expected.iter().position(|terminal| *terminal == END_MARKER) {
+            let end = expected.remove(end);
+            expected.push(end);
+        }
+        expected
+    }
+
+    /// Conjunto FOLLOW de um não terminal.
+    pub fn follow_of(&self, nonterminal: &str) -> BTreeSet<&str> {
+        self.follow
+            .get(nonterminal)
+            .map(|set| set.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,307 @@
+/// Interpretador da linguagem LSI-2024-2.
+///
+/// Executa a árvore sintática abstrata diretamente, a partir de uma função de entrada
+/// (`principal` por convenção). Todos os valores são inteiros de 32 bits:
+/// - expressões relacionais valem 1 (verdadeiro) ou 0 (falso), e a condição de um `if`
+///   é verdadeira quando é diferente de zero;
+/// - variáveis declaradas com `int` começam com 0;
+/// - `return;` retorna 0;
+/// - `print` escreve o valor seguido de uma quebra de linha.
+///
+/// Divisão por zero, estouro aritmético e recursão acima de `MAX_CALL_DEPTH` chamadas
+/// interrompem a execução com um erro. O programa deve ter passado pela análise semântica.
+///
+/// A execução é recursiva sobre a AST: com a pilha padrão, a recursão estouraria antes de chegar
+/// ao limite de chamadas. Quem executa programas arbitrários deve chamar `run` dentro de
+/// `on_large_stack`.
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::ast::{literal_value, BinOp, Expr, FuncCall, FuncDef, Ident, Program, RelOp, Stmt};
+use crate::diagnostic::Diagnostic;
+use crate::token::Span;
+
+/// Nome da função de entrada padrão.
+pub const DEFAULT_ENTRY: &str = "principal";
+
+/// Profundidade máxima de chamadas aninhadas.
+pub const MAX_CALL_DEPTH: usize = 1000;
+
+/// Tamanho da pilha usada por `on_large_stack`, suficiente para `MAX_CALL_DEPTH` chamadas
+/// aninhadas mesmo em builds sem otimização.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Erro de execução. O diagnóstico é guardado em uma caixa para que os resultados devolvidos
+/// a cada nível da recursão continuem pequenos.
+pub type RuntimeError = Box<Diagnostic>;
+
+/// Executa `f` em uma nova thread com pilha de `STACK_SIZE` bytes e aguarda o seu resultado.
+/// Um pânico em `f` é propagado para a thread atual.
+pub fn on_large_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        let thread = std::thread::Builder::new()
+            .name("interpreter".to_string())
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, f)
+            .expect("falha ao criar a thread do interpretador");
+        thread
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// Executa o programa a partir da função `entry`, escrevendo a saída de `print` em `out`.
+/// Um programa formado por um único comando (sem funções) é executado diretamente.
+///
+/// # Retorna
+/// - `Ok(i32)`: O valor retornado pela função de entrada (0 para um comando avulso).
+/// - `Err(RuntimeError)`: O erro de execução que interrompeu o programa.
+pub fn run(program: &Program, entry: &str, out: &mut dyn Write) -> Result<i32, RuntimeError> {
+    let mut interpreter = Interpreter {
+        functions: program
+            .functions
+            .iter()
+            .map(|function| (function.name.name.as_str(), function))
+            .collect(),
+        scopes: vec![HashMap::new()],
+        depth: 0,
+        out,
+    };
+
+    if let Some(statement) = &program.statement {
+        interpreter.exec(statement)?;
+        return Ok(0);
+    }
+
+    let Some(function) = interpreter.functions.get(entry).copied() else {
+        return Err(runtime_error(
+            format!("a função de entrada `{}` não foi definida", entry),
+            Span {
+                line: 1,
+                column: 1,
+                ..Span::default()
+            },
+        ));
+    };
+    if !function.params.is_empty() {
+        return Err(runtime_error(
+            format!(
+                "a função de entrada `{}` não pode receber parâmetros",
+                entry
+            ),
+            function.name.span,
+        ));
+    }
+    interpreter.invoke(function, vec![], function.name.span)
+}
+
+/// Cria um erro de execução em `span`.
+fn runtime_error(message: impl Into<String>, span: Span) -> RuntimeError {
+    Box::new(Diagnostic::error(message, span))
+}
+
+/// Resultado da execução de um comando.
+enum Flow {
+    Normal,      // Continua no próximo comando
+    Return(i32), // Encerra a função atual com o valor
+}
+
+/// Estado da execução.
+struct Interpreter<'a> {
+    functions: HashMap<&'a str, &'a FuncDef>,
+    scopes: Vec<HashMap<String, i32>>, // Escopos da função em execução, o último é o mais interno
+    depth: usize,                      // Número de chamadas em andamento
+    out: &'a mut dyn Write,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Executa `function` com os argumentos já avaliados, em um quadro de variáveis próprio.
+    fn invoke(
+        &mut self,
+        function: &'a FuncDef,
+        args: Vec<i32>,
+        at: Span,
+    ) -> Result<i32, RuntimeError> {
+        if self.depth == MAX_CALL_DEPTH {
+            return Err(runtime_error(
+                format!(
+                    "limite de {} chamadas aninhadas excedido ao chamar `{}`",
+                    MAX_CALL_DEPTH, function.name.name
+                ),
+                at,
+            ));
+        }
+
+        // Parâmetros e corpo compartilham o mesmo escopo
+        let frame = function
+            .params
+            .iter()
+            .map(|param| param.name.clone())
+            .zip(args)
+            .collect();
+        let caller = std::mem::replace(&mut self.scopes, vec![frame]);
+        self.depth += 1;
+
+        let mut result = Ok(0);
+        for stmt in &function.body {
+            match self.exec(stmt) {
+                Ok(Flow::Normal) => {}
+                Ok(Flow::Return(value)) => {
+                    result = Ok(value);
+                    break;
+                }
+                Err(error) => {
+                    result = Err(error);
+                    break;
+                }
+            }
+        }
+
+        self.depth -= 1;
+        self.scopes = caller;
+        result
+    }
+
+    fn exec(&mut self, stmt: &'a Stmt) -> Result<Flow, RuntimeError> {
+        match stmt {
+            Stmt::VarDecl(names) => {
+                let scope = self.scopes.last_mut().unwrap();
+                for name in names {
+                    scope.insert(name.name.clone(), 0);
+                }
+            }
+            Stmt::Assign { target, value } => {
+                let value = self.eval(value)?;
+                *self.lookup(target)? = value;
+            }
+            Stmt::Print(expr) => {
+                let value = self.eval(expr)?;
+                writeln!(self.out, "{}", value).map_err(|error| {
+                    runtime_error(
+                        format!("falha ao escrever a saída: {}", error),
+                        expr_span(expr),
+                    )
+                })?;
+            }
+            Stmt::Return(value) => {
+                let value = match value {
+                    Some(name) => *self.lookup(name)?,
+                    None => 0,
+                };
+                return Ok(Flow::Return(value));
+            }
+            Stmt::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                if self.eval(cond)? != 0 {
+                    return self.scoped(then_branch);
+                } else if let Some(else_branch) = else_branch {
+                    return self.scoped(else_branch);
+                }
+            }
+            Stmt::Block(stmts) => {
+                self.scopes.push(HashMap::new());
+                let mut flow = Ok(Flow::Normal);
+                for stmt in stmts {
+                    flow = self.exec(stmt);
+                    if !matches!(flow, Ok(Flow::Normal)) {
+                        break;
+                    }
+                }
+                self.scopes.pop();
+                return flow;
+            }
+            Stmt::Empty => {}
+        }
+        Ok(Flow::Normal)
+    }
+
+    /// Executa um ramo de `if` em um escopo próprio, como na análise semântica.
+    fn scoped(&mut self, stmt: &'a Stmt) -> Result<Flow, RuntimeError> {
+        self.scopes.push(HashMap::new());
+        let flow = self.exec(stmt);
+        self.scopes.pop();
+        flow
+    }
+
+    fn eval(&mut self, expr: &'a Expr) -> Result<i32, RuntimeError> {
+        match expr {
+            Expr::Num { value, .. } => Ok(literal_value(value)),
+            Expr::Var(name) => self.lookup(name).map(|value| *value),
+            Expr::Binary { op, lhs, rhs } => {
+                let (left, right) = (self.eval(lhs)?, self.eval(rhs)?);
+                if *op == BinOp::Div && right == 0 {
+                    return Err(runtime_error("divisão por zero", expr_span(expr)));
+                }
+                let result = match op {
+                    BinOp::Add => left.checked_add(right),
+                    BinOp::Sub => left.checked_sub(right),
+                    BinOp::Mul => left.checked_mul(right),
+                    BinOp::Div => left.checked_div(right),
+                };
+                result.ok_or_else(|| {
+                    runtime_error(
+                        format!("estouro aritmético em `{} {} {}`", left, op.as_str(), right),
+                        expr_span(expr),
+                    )
+                })
+            }
+            Expr::Relational { op, lhs, rhs } => {
+                let (left, right) = (self.eval(lhs)?, self.eval(rhs)?);
+                let result = match op {
+                    RelOp::Lt => left < right,
+                    RelOp::Le => left <= right,
+                    RelOp::Gt => left > right,
+                    RelOp::Ge => left >= right,
+                    RelOp::Eq => left == right,
+                    RelOp::Ne => left != right,
+                };
+                Ok(result as i32)
+            }
+            Expr::Call(call) => self.call(call),
+        }
+    }
+
+    fn call(&mut self, call: &'a FuncCall) -> Result<i32, RuntimeError> {
+        let name = &call.name;
+        let function = match self.functions.get(name.name.as_str()) {
+            Some(function) if function.params.len() == call.args.len() => *function,
+            _ => {
+                return Err(runtime_error(
+                    format!("chamada inválida à função `{}`", name.name),
+                    name.span,
+                ))
+            }
+        };
+
+        let args = call
+            .args
+            .iter()
+            .map(|arg| self.lookup(arg).map(|value| *value))
+            .collect::<Result<Vec<i32>, RuntimeError>>()?;
+        self.invoke(function, args, name.span)
+    }
+
+    /// Procura a variável `name` do escopo mais interno para o mais externo da função atual.
+    fn lookup(&mut self, name: &Ident) -> Result<&mut i32, RuntimeError> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(&name.name))
+            .ok_or_else(|| {
+                runtime_error(format!("variável `{}` não declarada", name.name), name.span)
+            })
+    }
+}
+
+/// Posição de uma expressão, usada nos erros de execução: a do seu primeiro operando.
+fn expr_span(expr: &Expr) -> Span {
+    match expr {
+        Expr::Num { span, .. } => *span,
+        Expr::Var(name) => name.span,
+        Expr::Binary { lhs, .. } | Expr::Relational { lhs, .. } => expr_span(lhs),
+        Expr::Call(call) => call.name.span,
+    }
+}
@@ -0,0 +1,238 @@
+/// Saída em JSON para ferramentas externas (`--format json`).
+///
+/// `Json` é um documento JSON montado em memória; o `Display` o escreve em uma única linha,
+/// com as chaves dos objetos na ordem em que foram inseridas. As funções `token`, `span`,
+/// `diagnostic` e `syntax_error` convertem as estruturas da análise, com chaves em inglês
+/// e sem textos além das mensagens dos diagnósticos.
+use std::fmt::{self, Write};
+
+use crate::diagnostic::{Diagnostic, LabelStyle, Severity};
+use crate::parser::SyntaxError;
+use crate::token::{Number, Span, Token, TokenValue};
+
+/// Valor JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>), // Pares chave-valor, na ordem de escrita
+}
+
+impl Json {
+    /// Objeto com os campos `fields`, na ordem dada.
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Json)>) -> Json {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    /// Valor do campo `key`, se este for um objeto que o contém.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Adiciona um campo ao fim do objeto. Não tem efeito em outros valores.
+    pub fn push(&mut self, key: &str, value: Json) {
+        if let Json::Object(fields) = self {
+            fields.push((key.to_string(), value));
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Json {
+        Json::Bool(value)
+    }
+}
+
+impl From<i32> for Json {
+    fn from(value: i32) -> Json {
+        Json::Number(value.into())
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Json {
+        Json::Number(value as i64)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Json {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Json {
+        Json::String(value)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> FromIterator<T> for Json {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Json {
+        Json::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_string(f, value),
+            Json::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(fields) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// Escreve uma string JSON, escapando aspas, barras invertidas e caracteres de controle.
+/// Os demais caracteres são escritos como estão (a saída é UTF-8).
+fn write_string(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// `{"start", "end", "line", "column"}`, com offsets em bytes e linhas e colunas a partir de 1.
+pub fn span(span: Span) -> Json {
+    Json::object([
+        ("start", span.start.into()),
+        ("end", span.end.into()),
+        ("line", span.line.into()),
+        ("column", span.column.into()),
+    ])
+}
+
+/// Token com o tipo, o lexema, o valor, o nome do terminal na gramática e a posição.
+/// O valor é `{"kind", "value"}`, com `value` numérico para números (ou uma string com os
+/// dígitos, para inteiros sem limite de tamanho).
+pub fn token(token: &Token) -> Json {
+    let value = match &token.value {
+        TokenValue::Lexeme(lexeme) => {
+            Json::object([("kind", "lexeme".into()), ("value", lexeme.as_str().into())])
+        }
+        TokenValue::Number(number) => {
+            let value = match number {
+                Number::I32(value) => Json::Number((*value).into()),
+                Number::I64(value) => Json::Number(*value),
+                Number::Big(digits) => digits.as_str().into(),
+            };
+            Json::object([("kind", "number".into()), ("value", value)])
+        }
+        TokenValue::RelopLabel(label) => {
+            Json::object([("kind", "relop".into()), ("value", label.as_str().into())])
+        }
+        TokenValue::ArithOpLabel(label) => {
+            Json::object([("kind", "arithop".into()), ("value", label.as_str().into())])
+        }
+    };
+    Json::object([
+        ("type", format!("{:?}", token.token_type).into()),
+        ("lexeme", token.lexeme.as_str().into()),
+        ("value", value),
+        ("terminal", token.terminal_name().into()),
+        ("span", span(token.span)),
+    ])
+}
+
+/// Diagnóstico da etapa `stage` (`lexical`, `syntax`, `semantic`, ...), com o código,
+/// a mensagem e a posição, além dos rótulos, notas e sugestões.
+pub fn diagnostic(diagnostic: &Diagnostic, stage: &str) -> Json {
+    let severity = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let labels = diagnostic.labels.iter().map(|label| {
+        let style = match label.style {
+            LabelStyle::Primary => "primary",
+            LabelStyle::Secondary => "secondary",
+        };
+        Json::object([
+            ("style", style.into()),
+            ("span", span(label.span)),
+            ("message", label.message.as_str().into()),
+        ])
+    });
+    let suggestions = diagnostic.suggestions.iter().map(|suggestion| {
+        Json::object([
+            ("message", suggestion.message.as_str().into()),
+            ("span", span(suggestion.span)),
+            ("replacement", suggestion.replacement.as_str().into()),
+        ])
+    });
+    Json::object([
+        ("stage", stage.into()),
+        ("severity", severity.into()),
+        ("code", diagnostic.code.into()),
+        ("message", diagnostic.message.as_str().into()),
+        ("span", span(diagnostic.span)),
+        ("labels", labels.collect()),
+        (
+            "notes",
+            diagnostic.notes.iter().map(String::as_str).collect(),
+        ),
+        ("suggestions", suggestions.collect()),
+    ])
+}
+
+/// Erro sintático como diagnóstico da etapa `syntax`, com os terminais esperados e o lexema
+/// encontrado (`null` no fim da entrada).
+pub fn syntax_error(error: &SyntaxError) -> Json {
+    let mut json = diagnostic(&error.to_diagnostic(), "syntax");
+    json.push(
+        "expected",
+        error.expected.iter().map(String::as_str).collect(),
+    );
+    json.push("found", error.found.as_deref().into());
+    json
+}
@@ -1,434 +1,447 @@
-#![allow(dead_code, unused)]
 /// Lexer para a linguagem LSI-2024-2
 /// Tokeniza a entrada fornecida em uma sequência de tokens.
 /// Prepara o input para a análise sintática.
+///
+/// Os tokens são descritos por expressões regulares em `grammars/lsi-2024-2.lex`, compiladas
+/// por `dfa` em um único autômato finito determinístico. A análise é uma única passada pela
+/// entrada: em cada posição, o autômato reconhece o maior lexema possível.
 use std::collections::HashSet;
-use std::env;
-use std::fs;
+use std::sync::OnceLock;
 
+use crate::dfa::{self, Dfa};
+use crate::diagnostic::Diagnostic;
 use crate::token::Terminal;
-use crate::token::{Token, TokenType, TokenValue};
-
-pub fn tokenize(
-    input: &str,
-    keywords: &HashSet<String>,
-    symbol_table: &mut HashSet<String>,
-) -> Result<Vec<Token>, String> {
-    let mut tokens = Vec::new();
-    let mut chars = input.chars().peekable();
-    let mut line = 1;
-    let mut column = 1;
-
-    while let Some(&ch) = chars.peek() {
-        // Ignora espaços em branco e novas linhas
-        if ch.is_whitespace() {
-            if ch == '\n' {
-                line += 1;
-                column = 1;
-            } else {
-                column += 1;
-            }
-            chars.next();
-            continue;
-        }
-
-        let mut max_token = None;
-        let mut max_length = 0;
-
-        // Tenta identificar diferentes tokens
-        let token_parsers: Vec<
-            fn(&mut std::iter::Peekable<std::str::Chars>, &HashSet<String>) -> Option<Token>,
-        > = vec![
-            parse_identifier,
-            parse_number,
-            parse_relop,
-            parse_arith_op,
-            parse_paren,
-            parse_assignment,
-            parse_comma,
-            parse_bracket,
-            parse_semicolon,
-        ];
+use crate::token::{Number, Span, Token, TokenType, TokenValue, Trivia, TriviaKind};
+
+/// Especificação léxica da LSI-2024-2 (ver o formato em `dfa`), incluída no executável.
+pub const LSI_TOKENS: &str = include_str!("../grammars/lsi-2024-2.lex");
+
+/// Palavras reservadas da LSI-2024-2.
+pub fn lsi_keywords() -> HashSet<String> {
+    [
+        "if".to_string(),
+        "else".to_string(),
+        "return".to_string(),
+        "int".to_string(),
+        "def".to_string(),
+        "num".to_string(),
+        "print".to_string(),
+    ]
+    .iter()
+    .cloned()
+    .collect()
+}
 
-        for parser in token_parsers {
-            let mut temp_chars = chars.clone();
-            if let Some(token) = parser(&mut temp_chars, keywords) {
-                if token.lexeme.len() > max_length {
-                    max_length = token.lexeme.len();
-                    max_token = Some(token);
-                }
-            }
+/// Reconstrói o código fonte a partir dos tokens e da trivia anexada a eles.
+///
+/// Para tokens produzidos por `tokenize`, o resultado é idêntico à entrada, exceto quando a
+/// entrada não possui nenhum token (não há onde anexar a trivia).
+pub fn reconstruct(tokens: &[Token]) -> String {
+    let mut source = String::new();
+    for token in tokens {
+        for trivia in &token.leading_trivia {
+            source.push_str(&trivia.text);
         }
-
-        if let Some(token) = max_token {
-            for _ in 0..token.lexeme.len() {
-                if let Some(ch) = chars.next() {
-                    if ch == '\n' {
-                        line += 1;
-                        column = 1;
-                    } else {
-                        column += 1;
-                    }
-                }
-            }
-            if let TokenType::Id = token.token_type {
-                symbol_table.insert(token.lexeme.clone());
-            }
-            tokens.push(token);
-        } else {
-            if let Some(error) = parse_wildcard(&mut chars, line, column) {
-                return Err(error);
-            }
+        source.push_str(&token.lexeme);
+        for trivia in &token.trailing_trivia {
+            source.push_str(&trivia.text);
         }
     }
+    source
+}
 
-    Ok(tokens)
+/// Largura dos literais inteiros, que define o intervalo aceito e a variante de `Number`
+/// nos tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IntegerWidth {
+    #[default]
+    I32, // Inteiros de 32 bits, como os da LSI-2024-2
+    I64,       // Inteiros de 64 bits
+    Arbitrary, // Sem limite: o valor é guardado como texto
 }
 
-fn parse_identifier(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
+/// Opções do analisador léxico.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LexerOptions {
+    pub compat_not_equal: bool, // Aceita `!=` como sinônimo de `<>`, com um aviso
+    pub integer_width: IntegerWidth, // Largura dos literais inteiros
+}
+
+/// Tokeniza a entrada completa com as opções padrão.
+///
+/// Caracteres não identificados não interrompem a análise: cada um gera um diagnóstico
+/// de erro e é descartado, de forma que todos os erros léxicos sejam reportados de uma vez.
+///
+/// Espaços em branco, comentários de linha (`# ...`) e de bloco (`/* ... */`) e caracteres
+/// descartados são preservados como trivia: cada token guarda a trivia que o precede e o
+/// último token guarda também a que o segue (ver `reconstruct`).
+///
+/// # Retorna
+/// A sequência de tokens reconhecidos e a lista de diagnósticos léxicos.
+pub fn tokenize(
+    input: &str,
     keywords: &HashSet<String>,
-) -> Option<Token> {
-    let mut lexeme = String::new();
-    let mut state = 0;
+    symbol_table: &mut HashSet<String>,
+) -> (Vec<Token>, Vec<Diagnostic>) {
+    tokenize_with_options(input, keywords, symbol_table, LexerOptions::default())
+}
 
-    while let Some(&ch) = chars.peek() {
-        match state {
-            0 => {
-                if ('a'..='z').contains(&ch) || ('A'..='Z').contains(&ch) {
-                    lexeme.push(ch);
-                    chars.next();
-                    state = 1;
-                } else {
-                    break;
-                }
-            }
-            1 => {
-                if ('a'..='z').contains(&ch) || ('A'..='Z').contains(&ch) || ch.is_digit(10) {
-                    lexeme.push(ch);
-                    chars.next();
-                } else {
-                    break;
-                }
-            }
-            _ => break,
-        }
-    }
+/// Tokeniza a entrada completa de acordo com `options`.
+///
+/// O operador diferente da LSI-2024-2 é `<>`. O `!=` é reconhecido para que a mensagem seja
+/// clara: é um erro, a menos que `options.compat_not_equal` esteja ativo, caso em que gera um
+/// aviso. Em ambos os casos o token produzido é `Terminal::NotEqual`, e a análise continua.
+///
+/// Os literais inteiros são lidos na largura `options.integer_width` (ver `number_value`).
+pub fn tokenize_with_options(
+    input: &str,
+    keywords: &HashSet<String>,
+    symbol_table: &mut HashSet<String>,
+    options: LexerOptions,
+) -> (Vec<Token>, Vec<Diagnostic>) {
+    lsi_lexer().tokenize(input, keywords, symbol_table, options)
+}
 
-    if lexeme.is_empty() {
-        return None; // Rejeita se nenhum identificador foi reconhecido
-    }
+/// Analisador léxico da LSI-2024-2, gerado na primeira vez que é usado.
+pub fn lsi_lexer() -> &'static Lexer {
+    static LEXER: OnceLock<Lexer> = OnceLock::new();
+    LEXER.get_or_init(|| match Lexer::from_spec(LSI_TOKENS) {
+        Ok(lexer) => lexer,
+        Err(diagnostics) => panic!(
+            "a especificação léxica embutida da LSI-2024-2 é inválida: {:?}",
+            diagnostics
+        ),
+    })
+}
 
-    // Verifica se o próximo caractere é um delimitador válido
-    if let Some(&next_ch) = chars.peek() {
-        if next_ch.is_alphanumeric() {
-            // Rejeita se o próximo caractere é alfanumérico
-            // Porque significa que o token não foi reconhecido completamente e o próximo caractere é parte dele
-            return None;
-        }
-    }
+/// O que o analisador léxico faz com o lexema de cada regra da especificação.
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Token(TokenType),    // Produz um token do tipo dado
+    Trivia(TriviaKind),  // Guarda o lexema como trivia
+    UnterminatedComment, // Comentário de bloco sem `*/`: erro, guardado como trivia
+    MalformedNumber,     // Número seguido de letras: erro, descartado
+}
 
-    if keywords.contains(&lexeme) {
-        Some(Token {
-            token_type: TokenType::Keyword,
-            lexeme: lexeme.clone(),
-            value: TokenValue::Lexeme(lexeme.clone()),
-            terminal: Terminal::from_str(&lexeme)?,
-        })
-    } else {
-        Some(Token {
-            token_type: TokenType::Id,
-            lexeme: lexeme.clone(),
-            value: TokenValue::Lexeme(lexeme),
-            terminal: Terminal::Id,
+impl Kind {
+    fn from_name(name: &str) -> Option<Kind> {
+        Some(match name {
+            "Id" => Kind::Token(TokenType::Id),
+            "Int" => Kind::Token(TokenType::Int),
+            "Relop" => Kind::Token(TokenType::Relop),
+            "ArithOp" => Kind::Token(TokenType::ArithOp),
+            "Assign" => Kind::Token(TokenType::Assign),
+            "Paren" => Kind::Token(TokenType::Paren),
+            "Comma" => Kind::Token(TokenType::Comma),
+            "Bracket" => Kind::Token(TokenType::Bracket),
+            "Semicolon" => Kind::Token(TokenType::Semicolon),
+            "Whitespace" => Kind::Trivia(TriviaKind::Whitespace),
+            "LineComment" => Kind::Trivia(TriviaKind::LineComment),
+            "BlockComment" => Kind::Trivia(TriviaKind::BlockComment),
+            "UnterminatedComment" => Kind::UnterminatedComment,
+            "MalformedNumber" => Kind::MalformedNumber,
+            _ => return None,
         })
     }
 }
 
-fn parse_number(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    _: &HashSet<String>,
-) -> Option<Token> {
-    let mut lexeme = String::new();
-    let mut state = 0;
+/// Analisador léxico gerado a partir de uma especificação léxica.
+#[derive(Debug)]
+pub struct Lexer {
+    dfa: Dfa,         // Autômato que reconhece os lexemas de todas as regras
+    kinds: Vec<Kind>, // O que fazer com o lexema de cada regra
+}
 
-    while let Some(&ch) = chars.peek() {
-        match state {
-            0 => {
-                if ch.is_digit(10) {
-                    lexeme.push(ch);
-                    chars.next();
-                    state = 1;
-                } else {
-                    break;
-                }
-            }
-            1 => {
-                if ch.is_digit(10) {
-                    lexeme.push(ch);
-                    chars.next();
-                } else {
-                    break;
-                }
+impl Lexer {
+    /// Lê a especificação léxica `source` e gera o seu autômato.
+    ///
+    /// # Retorna
+    /// - `Ok(Lexer)`: O analisador léxico.
+    /// - `Err(Vec<Diagnostic>)`: Erros na especificação, como expressões regulares inválidas ou
+    ///   regras com nomes que o analisador léxico não conhece.
+    pub fn from_spec(source: &str) -> Result<Lexer, Vec<Diagnostic>> {
+        let rules = dfa::parse_spec(source)?;
+        let mut kinds = vec![];
+        let mut diagnostics = vec![];
+        for rule in &rules {
+            match Kind::from_name(&rule.name) {
+                Some(kind) => kinds.push(kind),
+                None => diagnostics.push(Diagnostic::error(
+                    format!("regra `{}` desconhecida pelo analisador léxico", rule.name),
+                    rule.span,
+                )),
             }
-            _ => break,
         }
-    }
-
-    if lexeme.is_empty() {
-        return None; // Rejeita se nenhum número foi reconhecido
-    }
-
-    // Verifica se o próximo caractere é um delimitador válido
-    if let Some(&next_ch) = chars.peek() {
-        if next_ch.is_alphanumeric() {
-            // Rejeita se o próximo caractere é alfanumérico
-            // Porque significa que o token não foi reconhecido completamente e o próximo caractere é parte dele
-            return None;
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
         }
+        Ok(Lexer {
+            dfa: dfa::compile(&rules)?,
+            kinds,
+        })
     }
 
-    let value = lexeme.parse::<i32>().unwrap();
-
-    Some(Token {
-        token_type: TokenType::Int,
-        lexeme: lexeme.clone(),
-        value: TokenValue::Number(value),
-        terminal: Terminal::Num,
-    })
-}
-
-fn parse_relop(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    _: &HashSet<String>,
-) -> Option<Token> {
-    let mut lexeme = String::new();
-    let mut state = 0;
+    /// Autômato do analisador léxico.
+    pub fn dfa(&self) -> &Dfa {
+        &self.dfa
+    }
 
-    while let Some(&ch) = chars.peek() {
-        match state {
-            0 => {
-                if ch == '=' || ch == '!' {
-                    lexeme.push(ch);
-                    chars.next();
-                    state = 1;
-                } else if ch == '<' || ch == '>' {
-                    lexeme.push(ch);
-                    chars.next();
-                    state = 2;
-                } else {
-                    break;
+    /// Tokeniza a entrada completa de acordo com `options` (ver `tokenize_with_options`).
+    pub fn tokenize(
+        &self,
+        input: &str,
+        keywords: &HashSet<String>,
+        symbol_table: &mut HashSet<String>,
+        options: LexerOptions,
+    ) -> (Vec<Token>, Vec<Diagnostic>) {
+        let mut tokens = Vec::new();
+        let mut diagnostics = Vec::new();
+        let mut offset = 0;
+        let mut line = 1;
+        let mut column = 1;
+
+        // Trivia encontrada desde o último token, anexada ao próximo token reconhecido
+        let mut trivia: Vec<Trivia> = Vec::new();
+
+        while offset < input.len() {
+            let start = Span {
+                start: offset,
+                end: offset,
+                line,
+                column,
+            };
+            let rest = &input[offset..];
+
+            let Some((rule, length)) = self.dfa.longest_match(rest) else {
+                // Caractere não identificado: é descartado (e guardado como trivia, para que
+                // o código possa ser reconstruído), e a análise continua no próximo
+                let ch = rest.chars().next().unwrap();
+                advance(&rest[..ch.len_utf8()], &mut offset, &mut line, &mut column);
+                let span = Span {
+                    end: offset,
+                    ..start
+                };
+                diagnostics.push(
+                    Diagnostic::error(format!("caractere não identificado '{}'", ch), span)
+                        .with_code("E0001")
+                        .with_label("caractere inválido na LSI-2024-2"),
+                );
+                trivia.push(Trivia {
+                    kind: TriviaKind::Skipped,
+                    text: ch.to_string(),
+                    span,
+                });
+                continue;
+            };
+
+            let lexeme = &rest[..length];
+            advance(lexeme, &mut offset, &mut line, &mut column);
+            let span = Span {
+                end: offset,
+                ..start
+            };
+            let skipped = |kind| Trivia {
+                kind,
+                text: lexeme.to_string(),
+                span,
+            };
+
+            match self.kinds[rule] {
+                Kind::Trivia(kind) => trivia.push(skipped(kind)),
+                Kind::UnterminatedComment => {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            "comentário de bloco não terminado; esperado `*/` antes do fim do arquivo",
+                            Span {
+                                end: start.start + lexeme.len().min(2),
+                                ..start
+                            },
+                        )
+                        .with_code("E0002")
+                        .with_label("comentário iniciado aqui")
+                        .with_note("o restante do arquivo foi tratado como comentário"),
+                    );
+                    trivia.push(skipped(TriviaKind::BlockComment));
                 }
-            }
-            1 => {
-                if ch == '=' {
-                    lexeme.push(ch);
-                    chars.next();
-                    state = 4;
-                } else {
-                    state = 3;
+                Kind::MalformedNumber => {
+                    diagnostics.push(
+                        Diagnostic::error(format!("número mal formado `{}`", lexeme), span)
+                            .with_code("E0006")
+                            .with_label("um número não pode ser seguido de letras")
+                            .with_note("identificadores começam com uma letra"),
+                    );
+                    trivia.push(skipped(TriviaKind::Skipped));
                 }
-            }
-            2 => {
-                if ch == '=' {
-                    lexeme.push(ch);
-                    chars.next();
-                    state = 4;
-                } else {
-                    state = 3;
+                Kind::Token(token_type) => {
+                    let mut token = token(token_type, lexeme, keywords);
+                    token.span = span;
+                    token.leading_trivia = std::mem::take(&mut trivia);
+                    match token.token_type {
+                        TokenType::Id => {
+                            symbol_table.insert(token.lexeme.clone());
+                        }
+                        TokenType::Int => {
+                            let value =
+                                number_value(&token, options.integer_width, &mut diagnostics);
+                            token.value = TokenValue::Number(value);
+                        }
+                        _ => {}
+                    }
+                    if token.lexeme == "!=" {
+                        let diagnostic = if options.compat_not_equal {
+                            Diagnostic::warning(
+                                "`!=` não faz parte da LSI-2024-2 e foi aceito como `<>`",
+                                token.span,
+                            )
+                            .with_code("W0001")
+                        } else {
+                            Diagnostic::error(
+                                "operador `!=` inválido; o operador diferente da LSI-2024-2 é `<>`",
+                                token.span,
+                            )
+                            .with_code("E0003")
+                            .with_label("operador inválido")
+                            .with_note("a opção --compat-ne aceita `!=` com um aviso")
+                        };
+                        diagnostics.push(diagnostic.with_suggestion("use `<>`", token.span, "<>"));
+                    }
+                    tokens.push(token);
                 }
             }
-            3 => break,
-            4 => break,
-            _ => break,
         }
-    }
-
-    if lexeme.is_empty() {
-        return None; // Rejeita se nenhum operador relacional foi reconhecido
-    }
-
-    let relop_label = match lexeme.as_str() {
-        "==" => "EQ".to_string(),
-        "!=" => "NE".to_string(),
-        "<" => "LT".to_string(),
-        "<=" => "LE".to_string(),
-        ">" => "GT".to_string(),
-        ">=" => "GE".to_string(),
-        _ => "".to_string(),
-    };
-
-    Some(Token {
-        token_type: TokenType::Relop,
-        lexeme: lexeme.clone(),
-        value: TokenValue::RelopLabel(relop_label.clone()),
-        terminal: Terminal::from_str(&lexeme)?,
-    })
-}
-
-/*
-* Parser de caractere não identificado (wildcard)
-* Consome o caractere e retorna uma mensagem de erro
-*/
-fn parse_wildcard(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    line: usize,
-    column: usize,
-) -> Option<String> {
-    if let Some(&ch) = chars.peek() {
-        chars.next(); // Consome o caractere
-        Some(format!(
-            "Erro: Caractere não identificado '{}' na linha {}, coluna {}",
-            ch, line, column
-        ))
-    } else {
-        None
-    }
-}
-
-fn parse_args(args: &[String]) -> Result<&str, &str> {
-    if args.len() != 2 {
-        return Err("O caminho do arquivo de entrada deve ser fornecido como argumento. Por exemplo: cargo run entrada-A.txt");
-    }
-    let file_path = &args[1];
-    Ok(file_path)
-}
 
-fn parse_arith_op(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    _: &HashSet<String>,
-) -> Option<Token> {
-    if let Some(&ch) = chars.peek() {
-        if "+-*/".contains(ch) {
-            let lexeme = ch.to_string();
-            chars.next();
-            return Some(Token {
-                token_type: TokenType::ArithOp,
-                lexeme: lexeme.clone(),
-                value: TokenValue::ArithOpLabel(lexeme.clone()),
-                terminal: Terminal::from_str(&lexeme)?,
-            });
+        // A trivia após o último token fica no próprio último token
+        if let Some(last) = tokens.last_mut() {
+            last.trailing_trivia = trivia;
         }
-    }
-    None
-}
 
-fn parse_paren(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    _: &HashSet<String>,
-) -> Option<Token> {
-    if let Some(&ch) = chars.peek() {
-        if ch == '(' || ch == ')' {
-            let lexeme = ch.to_string();
-            chars.next();
-            return Some(Token {
-                token_type: TokenType::Paren,
-                lexeme: lexeme.clone(),
-                value: TokenValue::Lexeme(lexeme.clone()),
-                terminal: Terminal::from_str(&lexeme)?,
-            });
-        }
+        (tokens, diagnostics)
     }
-    None
 }
 
-fn parse_assignment(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    _: &HashSet<String>,
-) -> Option<Token> {
-    let mut lexeme = String::new();
-    let mut state = 0;
-
-    while let Some(&ch) = chars.peek() {
-        match state {
-            0 => {
-                if ch == ':' {
-                    lexeme.push(ch);
-                    chars.next();
-                    state = 1;
-                } else {
-                    break;
-                }
-            }
-            1 => {
-                if ch == '=' {
-                    lexeme.push(ch);
-                    chars.next();
-                    state = 2;
-                } else {
-                    break;
-                }
-            }
-            2 => break,
-            _ => break,
+/// Avança a posição sobre `text`, atualizando o offset, a linha e a coluna.
+fn advance(text: &str, offset: &mut usize, line: &mut usize, column: &mut usize) {
+    for ch in text.chars() {
+        *offset += ch.len_utf8();
+        if ch == '\n' {
+            *line += 1;
+            *column = 1;
+        } else {
+            *column += 1;
         }
     }
-
-    if lexeme == ":=" {
-        Some(Token {
-            token_type: TokenType::Assign,
-            lexeme: lexeme.clone(),
-            value: TokenValue::Lexeme(lexeme),
-            terminal: Terminal::Equals,
-        })
-    } else {
-        None
-    }
 }
 
-fn parse_comma(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    _: &HashSet<String>,
-) -> Option<Token> {
-    if let Some(&ch) = chars.peek() {
-        if ch == ',' {
-            let lexeme = ch.to_string();
-            chars.next();
-            return Some(Token {
-                token_type: TokenType::Comma,
-                lexeme: lexeme.clone(),
-                value: TokenValue::Lexeme(lexeme),
-                terminal: Terminal::Comma,
-            });
+/// Token do tipo `token_type` com o lexema `lexeme`, ainda sem posição.
+///
+/// Identificadores que estão em `keywords` são palavras reservadas. O terminal é o do lexema
+/// (ou `Terminal::Keyword`, que usa o próprio lexema como nome, se ele não for um terminal da
+/// LSI-2024-2). O valor dos números é calculado depois, por `number_value`.
+fn token(token_type: TokenType, lexeme: &str, keywords: &HashSet<String>) -> Token {
+    let terminal = Terminal::from_str(lexeme).unwrap_or(Terminal::Keyword);
+    let (token_type, value, terminal) = match token_type {
+        TokenType::Id if keywords.contains(lexeme) => (
+            TokenType::Keyword,
+            TokenValue::Lexeme(lexeme.to_string()),
+            terminal,
+        ),
+        TokenType::Id => (
+            TokenType::Id,
+            TokenValue::Lexeme(lexeme.to_string()),
+            Terminal::Id,
+        ),
+        TokenType::Int => (
+            TokenType::Int,
+            TokenValue::Lexeme(lexeme.to_string()),
+            Terminal::Num,
+        ),
+        TokenType::Relop => {
+            let label = match lexeme {
+                "==" => "EQ",
+                "<>" | "!=" => "NE",
+                "<" => "LT",
+                "<=" => "LE",
+                ">" => "GT",
+                ">=" => "GE",
+                _ => "",
+            };
+            let terminal = match lexeme {
+                "!=" => Terminal::NotEqual,
+                _ => terminal,
+            };
+            (
+                TokenType::Relop,
+                TokenValue::RelopLabel(label.to_string()),
+                terminal,
+            )
         }
+        TokenType::ArithOp => (
+            TokenType::ArithOp,
+            TokenValue::ArithOpLabel(lexeme.to_string()),
+            terminal,
+        ),
+        token_type => (token_type, TokenValue::Lexeme(lexeme.to_string()), terminal),
+    };
+    Token {
+        token_type,
+        lexeme: lexeme.to_string(),
+        value,
+        terminal,
+        span: Span::default(),
+        leading_trivia: vec![],
+        trailing_trivia: vec![],
     }
-    None
 }
 
-fn parse_bracket(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    _: &HashSet<String>,
-) -> Option<Token> {
-    if let Some(&ch) = chars.peek() {
-        if ch == '{' || ch == '}' {
-            let lexeme = ch.to_string();
-            chars.next();
-            return Some(Token {
-                token_type: TokenType::Bracket,
-                lexeme: lexeme.clone(),
-                value: TokenValue::Lexeme(lexeme.clone()),
-                terminal: Terminal::from_str(&lexeme)?,
-            });
-        }
+/// Valor do literal inteiro `token` na largura `width`.
+///
+/// Literais com zeros à esquerda (`007`) são um erro, já que em outras linguagens `010` é
+/// octal; o valor é lido sem os zeros. Um literal fora do intervalo da largura também é um
+/// erro e vale o maior valor do intervalo, para que a análise continue.
+fn number_value(token: &Token, width: IntegerWidth, diagnostics: &mut Vec<Diagnostic>) -> Number {
+    let digits = match token.lexeme.trim_start_matches('0') {
+        "" => "0",
+        digits => digits,
+    };
+    if digits.len() < token.lexeme.len() && token.lexeme != "0" {
+        diagnostics.push(
+            Diagnostic::error(
+                format!("literal inteiro `{}` com zeros à esquerda", token.lexeme),
+                token.span,
+            )
+            .with_code("E0005")
+            .with_label("zeros à esquerda não são permitidos")
+            .with_note("em outras linguagens, como C, `010` é um número octal")
+            .with_suggestion("remova os zeros à esquerda", token.span, digits),
+        );
     }
-    None
-}
 
-fn parse_semicolon(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    _: &HashSet<String>,
-) -> Option<Token> {
-    if let Some(&ch) = chars.peek() {
-        if ch == ';' {
-            let lexeme = ch.to_string();
-            chars.next();
-            return Some(Token {
-                token_type: TokenType::Semicolon,
-                lexeme: lexeme.clone(),
-                value: TokenValue::Lexeme(lexeme),
-                terminal: Terminal::Semicolon,
-            });
-        }
-    }
-    None
+    let (value, max, bits) = match width {
+        IntegerWidth::I32 => (
+            digits.parse().map(Number::I32).ok(),
+            Number::I32(i32::MAX),
+            32,
+        ),
+        IntegerWidth::I64 => (
+            digits.parse().map(Number::I64).ok(),
+            Number::I64(i64::MAX),
+            64,
+        ),
+        IntegerWidth::Arbitrary => return Number::Big(digits.to_string()),
+    };
+    value.unwrap_or_else(|| {
+        diagnostics.push(
+            Diagnostic::error(
+                format!(
+                    "literal inteiro `{}` fora do intervalo dos inteiros de {} bits",
+                    token.lexeme, bits
+                ),
+                token.span,
+            )
+            .with_code("E0004")
+            .with_label(format!("maior que {}", max))
+            .with_note(format!("o maior literal inteiro aceito é {}", max)),
+        );
+        max
+    })
 }
@@ -0,0 +1,56 @@
+//! Front end da linguagem LSI-2024-2: analisadores léxico e sintático preditivo.
+//!
+//! A biblioteca expõe as etapas da análise para uso por outras ferramentas:
+//! - `tokenize` transforma o código fonte em tokens e diagnósticos léxicos, com um autômato
+//!   gerado por `dfa` a partir das expressões regulares dos tokens;
+//! - `parse` constrói a árvore sintática abstrata (`ast::Program`) a partir dos tokens;
+//! - `semantic::check` verifica as declarações de variáveis e as chamadas de função;
+//! - `interpreter::run` executa o programa;
+//! - `tac::lower` traduz o programa para código de três endereços;
+//! - `bytecode::compile` gera o bytecode executado por `vm::run`;
+//! - `x86_64::generate` gera assembly x86-64 para o GNU as;
+//! - `c::generate` traduz o programa para C;
+//! - `wat::generate` gera um módulo WebAssembly em formato de texto;
+//! - `json` converte tokens e diagnósticos para JSON;
+//! - `grammar` e `bnf` descrevem gramáticas e geram as suas tabelas LL(1).
+//!
+//! ```
+//! use std::collections::HashSet;
+//!
+//! let keywords = parser::lsi_keywords();
+//! let mut symbol_table: HashSet<String> = keywords.clone();
+//! let (tokens, diagnostics) = parser::tokenize("def principal() { }", &keywords, &mut symbol_table);
+//! assert!(diagnostics.is_empty());
+//! let program = parser::parse(tokens).expect("programa válido");
+//! assert_eq!(program.functions.len(), 1);
+//! ```
+pub mod ast;
+pub mod bnf;
+pub mod bytecode;
+pub mod c;
+pub mod dfa;
+pub mod diagnostic;
+pub mod grammar;
+pub mod interpreter;
+pub mod json;
+pub mod lexer;
+pub mod ll1_table;
+pub mod parser;
+pub mod semantic;
+pub mod tac;
+pub mod token;
+pub mod vm;
+pub mod wat;
+pub mod x86_64;
+
+// Os exemplos em Rust do README são executados como testes de documentação
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+pub struct ReadmeDoctests;
+
+pub use ast::Program;
+pub use diagnostic::{Diagnostic, Severity};
+pub use grammar::{Grammar, ParseTable};
+pub use lexer::{lsi_keywords, tokenize};
+pub use parser::{parse, parse_tree, ParseObserver, SyntaxError};
+pub use token::{Number, Span, Terminal, Token};
@@ -1,6 +1,3 @@
-use std::collections::HashSet;
-use std::env;
-use std::fs;
 /**
  * T3 de compiladores: Parser preditivo
  * Esse programa é um analisador sintático que implementa um parser preditivo para a linguagem de programação LSI-2024-2.
@@ -9,62 +6,506 @@ use std::fs;
  * - Vitor Matheus Valandro da Rosa (22102567)
  * - Pedro Henrique Nascimento Rocha (22100918)
  */
-mod lexer;
-mod ll1_table;
-mod parser;
-mod token;
-
-use lexer::tokenize;
-use parser::parse;
+use parser::bnf::{grammar_keywords, parse_grammar};
+use parser::diagnostic::Renderer;
+use parser::grammar::Grammar;
+use parser::json::{self, Json};
+use parser::lexer::{tokenize_with_options, IntegerWidth, LexerOptions};
+use parser::ll1_table::lsi_grammar;
+use parser::parser::{parse_tree_with_observer, parse_with_observer, ParseObserver, Quiet, Trace};
+use parser::{bytecode, c, interpreter, lsi_keywords, semantic, tac, vm, wat, x86_64, Program};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::IsTerminal;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    let file_path = parse_args(&args).unwrap_or_else(|err| {
+    let options = parse_args(&args).unwrap_or_else(|err| {
         eprintln!("Error: {}", err);
         std::process::exit(1);
     });
 
+    // Gramática carregada de arquivo com `--grammar`, no lugar da LSI-2024-2
+    let custom_grammar = options.grammar_path.map(load_grammar);
+
+    if options.grammar_report {
+        match &custom_grammar {
+            Some(grammar) => print_grammar_report(grammar),
+            None => print_grammar_report(lsi_grammar()),
+        }
+        return;
+    }
+
+    let file_path = options.file_path.unwrap_or_else(|| {
+        eprintln!("Error: {}", USAGE);
+        std::process::exit(1);
+    });
+
     let contents = fs::read_to_string(file_path).expect("Erro ao ler arquivo");
 
-    let keywords: HashSet<String> = [
-        "if".to_string(),
-        "else".to_string(),
-        "return".to_string(),
-        "int".to_string(),
-        "def".to_string(),
-        "num".to_string(),
-        "print".to_string(),
-    ]
-    .iter()
-    .cloned()
-    .collect();
+    let keywords: HashSet<String> = match &custom_grammar {
+        Some(grammar) => grammar_keywords(grammar),
+        None => lsi_keywords(),
+    };
 
     let mut symbol_table: HashSet<String> = keywords.clone();
+    let renderer = renderer(&contents, file_path);
+
+    let lexer_options = LexerOptions {
+        compat_not_equal: options.compat_not_equal,
+        integer_width: options.integer_width,
+    };
+
+    if let Format::Json = options.format {
+        check_json(
+            &contents,
+            file_path,
+            &keywords,
+            lexer_options,
+            custom_grammar.as_ref(),
+        );
+    }
+
+    if let (Command::Interpret, _) | (_, Some(_)) = (&options.command, options.emit) {
+        if custom_grammar.is_some() {
+            eprintln!("Error: interpret e --emit usam apenas a gramática da LSI-2024-2.");
+            std::process::exit(1);
+        }
+        let program = analyze(&renderer, &keywords, lexer_options);
+        if let Command::Interpret = options.command {
+            interpret(&program, &renderer, options.entry, options.vm);
+        }
+        if let Some(format) = options.emit {
+            emit(&program, format, options.entry);
+        }
+        return;
+    }
+
+    let (tokens, diagnostics) =
+        tokenize_with_options(&contents, &keywords, &mut symbol_table, lexer_options);
+
+    // Os tokens e a árvore só são impressos com `--dump`
+    if options.dump {
+        println!("Lista de tokens:");
+        for token in &tokens {
+            println!("{:?}", token);
+        }
+    }
+
+    // Erros léxicos não impedem a análise sintática dos tokens reconhecidos
+    let mut failed = false;
+    if !diagnostics.is_empty() {
+        eprintln!(
+            "\nAnálise léxica encontrou {} problema(s):",
+            diagnostics.len()
+        );
+        for diagnostic in &diagnostics {
+            eprintln!("{}", renderer.render(diagnostic));
+        }
+        failed = diagnostics.iter().any(|diagnostic| diagnostic.is_error());
+    }
+
+    // O rastro da análise só é impresso com `--trace`
+    let observer: &mut dyn ParseObserver = if options.trace {
+        println!("Rastro da análise sintática:");
+        &mut Trace
+    } else {
+        &mut Quiet
+    };
+
+    if let Some(grammar) = custom_grammar {
+        match parse_tree_with_observer(tokens, &grammar.ll1_table(), observer) {
+            Ok(tree) => {
+                println!("Análise sintática bem-sucedida.");
+                if options.dump {
+                    println!("Árvore de derivação:\n{}", tree);
+                }
+            }
+            Err(errors) => {
+                eprintln!("\nParsing falhou com {} erro(s):", errors.len());
+                for error in &errors {
+                    eprintln!("{}", renderer.render(&error.to_diagnostic()));
+                }
+                failed = true;
+            }
+        }
+    } else {
+        match parse_with_observer(tokens, observer) {
+            Ok(program) => {
+                println!("Análise sintática bem-sucedida.");
+                if options.dump {
+                    println!("Árvore sintática abstrata:\n{:#?}", program);
+                }
+
+                let diagnostics = semantic::check(&program);
+                if !diagnostics.is_empty() {
+                    eprintln!(
+                        "\nAnálise semântica encontrou {} problema(s):",
+                        diagnostics.len()
+                    );
+                    for diagnostic in &diagnostics {
+                        eprintln!("{}", renderer.render(diagnostic));
+                    }
+                }
+                failed |= diagnostics.iter().any(|diagnostic| diagnostic.is_error());
+            }
+            Err(errors) => {
+                eprintln!("\nParsing falhou com {} erro(s):", errors.len());
+                for error in &errors {
+                    eprintln!("{}", renderer.render(&error.to_diagnostic()));
+                }
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+const USAGE: &str = "O caminho do arquivo de entrada deve ser fornecido como argumento. Por exemplo: cargo run entrada.txt ou cargo run interpret entrada.txt";
+
+/// Executa as análises léxica, sintática e semântica sem imprimir a AST, como etapa anterior
+/// à execução e à geração de código. Os diagnósticos vão para a saída de erro, e o processo
+/// é encerrado se houver algum erro.
+fn analyze(
+    renderer: &Renderer,
+    keywords: &HashSet<String>,
+    lexer_options: LexerOptions,
+) -> Program {
+    let mut symbol_table = keywords.clone();
+    let (tokens, mut diagnostics) = tokenize_with_options(
+        renderer.source(),
+        keywords,
+        &mut symbol_table,
+        lexer_options,
+    );
+
+    let program = match parse_with_observer(tokens, &mut Quiet) {
+        Ok(program) => Some(program),
+        Err(errors) => {
+            diagnostics.extend(errors.iter().map(|error| error.to_diagnostic()));
+            None
+        }
+    };
+    if let Some(program) = &program {
+        diagnostics.extend(semantic::check(program));
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    for diagnostic in &diagnostics {
+        eprintln!("{}", renderer.render(diagnostic));
+    }
+    match program {
+        Some(program) if !diagnostics.iter().any(|diagnostic| diagnostic.is_error()) => program,
+        _ => {
+            eprintln!("O programa contém erros.");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Executa as análises léxica, sintática e semântica (esta apenas com a gramática da
+/// LSI-2024-2) e imprime um único documento JSON com os tokens, os diagnósticos de todas as
+/// etapas e o resultado da análise sintática. O processo termina com erro se houver algum erro.
+fn check_json(
+    source: &str,
+    path: &str,
+    keywords: &HashSet<String>,
+    lexer_options: LexerOptions,
+    grammar: Option<&Grammar>,
+) -> ! {
+    let mut symbol_table = keywords.clone();
+    let (tokens, lexical) =
+        tokenize_with_options(source, keywords, &mut symbol_table, lexer_options);
+    let token_list: Json = tokens.iter().map(json::token).collect();
 
-    match tokenize(&contents, &keywords, &mut symbol_table) {
-        Ok(tokens) => {
-            println!("Lista de tokens:");
-            for token in &tokens {
-                println!("{:?}", token);
+    let mut diagnostics: Vec<Json> = lexical
+        .iter()
+        .map(|diagnostic| json::diagnostic(diagnostic, "lexical"))
+        .collect();
+    let mut failed = lexical.iter().any(|diagnostic| diagnostic.is_error());
+
+    let result = match grammar {
+        Some(grammar) => {
+            parse_tree_with_observer(tokens, &grammar.ll1_table(), &mut Quiet).map(|_| None)
+        }
+        None => parse_with_observer(tokens, &mut Quiet).map(Some),
+    };
+    let syntax_errors = match result {
+        Ok(program) => {
+            if let Some(program) = program {
+                let semantic = semantic::check(&program);
+                failed |= semantic.iter().any(|diagnostic| diagnostic.is_error());
+                diagnostics.extend(
+                    semantic
+                        .iter()
+                        .map(|diagnostic| json::diagnostic(diagnostic, "semantic")),
+                );
             }
+            0
+        }
+        Err(errors) => {
+            failed = true;
+            diagnostics.extend(errors.iter().map(json::syntax_error));
+            errors.len()
+        }
+    };
+
+    let document = Json::object([
+        ("file", path.into()),
+        ("success", (!failed).into()),
+        ("tokens", token_list),
+        ("diagnostics", Json::Array(diagnostics)),
+        (
+            "parse",
+            Json::object([
+                ("success", (syntax_errors == 0).into()),
+                ("errors", syntax_errors.into()),
+            ]),
+        ),
+    ]);
+    println!("{}", document);
+    std::process::exit(i32::from(failed));
+}
 
-            match parse(tokens) {
-                Ok(message) => println!("{}", message),
-                Err(err) => eprintln!("\nParsing falhou: {}", err),
+/// Executa o programa com o interpretador e encerra o processo.
+/// A saída padrão contém apenas o que o programa imprime; os diagnósticos vão para a saída de erro.
+/// Com `vm`, o programa é compilado para bytecode e executado pela máquina virtual.
+fn interpret(program: &Program, renderer: &Renderer, entry: &str, vm: bool) -> ! {
+    let stdout = std::io::stdout();
+    let result = if vm {
+        match bytecode::compile(&tac::lower(program)) {
+            Ok(module) => {
+                vm::run(&module, entry, &mut stdout.lock()).map_err(|error| format!("{}\n", error))
             }
+            Err(error) => Err(format!("Error: {}\n", error)),
         }
+    } else {
+        // A execução é recursiva e precisa de uma pilha maior que a da thread principal
+        interpreter::on_large_stack(|| interpreter::run(program, entry, &mut std::io::stdout()))
+            .map_err(|error| renderer.render(&error))
+    };
+
+    match result {
+        Ok(_) => std::process::exit(0),
         Err(error) => {
-            eprintln!("{}", error);
+            eprint!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Imprime o programa traduzido para o formato `emit`.
+/// Os formatos executáveis chamam a função `entry` no início do programa.
+fn emit(program: &Program, emit: Emit, entry: &str) {
+    let code = match emit {
+        Emit::Tac => Ok(tac::lower(program).to_string()),
+        Emit::Bytecode => {
+            bytecode::compile(&tac::lower(program)).map(|module| bytecode::disassemble(&module))
+        }
+        Emit::Asm => x86_64::generate(&tac::lower(program), entry),
+        Emit::C => c::generate(program, entry),
+        Emit::Wat => Ok(wat::generate(program)),
+    };
+    match code {
+        Ok(code) => print!("{}", code),
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Formatos de saída de `--emit`.
+#[derive(Clone, Copy)]
+enum Emit {
+    Tac,      // Código de três endereços
+    Bytecode, // Bytecode da máquina virtual, desmontado
+    Asm,      // Assembly x86-64 (GNU as)
+    C,        // Código C
+    Wat,      // Módulo WebAssembly em formato de texto
+}
+
+impl Emit {
+    fn from_str(name: &str) -> Option<Emit> {
+        match name {
+            "tac" => Some(Emit::Tac),
+            "bytecode" => Some(Emit::Bytecode),
+            "asm" => Some(Emit::Asm),
+            "c" => Some(Emit::C),
+            "wat" => Some(Emit::Wat),
+            _ => None,
+        }
+    }
+}
+
+/// Formatos de saída de `--format`.
+#[derive(Clone, Copy)]
+enum Format {
+    Text, // Padrão: tokens, AST e diagnósticos para leitura
+    Json, // Um único documento JSON na saída padrão
+}
+
+/// Subcomandos da linha de comando.
+enum Command {
+    Check,     // Padrão: análise léxica, sintática e semântica
+    Interpret, // `interpret`: executa o programa
+}
+
+/// Opções de linha de comando.
+struct Options<'a> {
+    command: Command,              // Subcomando
+    entry: &'a str, // `--entry <função>`: função de entrada do `interpret` e de `--emit asm`/`c`
+    file_path: Option<&'a str>, // Arquivo de entrada
+    grammar_path: Option<&'a str>, // `--grammar <arquivo>`: gramática BNF usada no lugar da LSI-2024-2
+    grammar_report: bool,          // `--grammar-report`: lista os conflitos LL(1) da gramática
+    trace: bool,                   // `--trace`: imprime cada passo da análise sintática
+    dump: bool,                    // `--dump`: imprime os tokens e a árvore sintática
+    compat_not_equal: bool,        // `--compat-ne`: aceita `!=` como `<>`, com um aviso
+    emit: Option<Emit>,            // `--emit <formato>`: imprime o programa traduzido
+    vm: bool,                      // `--vm`: o `interpret` usa a máquina virtual de bytecode
+    format: Format,                // `--format <formato>`: formato da saída da análise
+    integer_width: IntegerWidth,   // `--int-width <largura>`: largura dos literais inteiros
+}
+
+fn parse_args(args: &[String]) -> Result<Options<'_>, String> {
+    let mut options = Options {
+        command: Command::Check,
+        entry: interpreter::DEFAULT_ENTRY,
+        file_path: None,
+        grammar_path: None,
+        grammar_report: false,
+        trace: false,
+        dump: false,
+        compat_not_equal: false,
+        emit: None,
+        vm: false,
+        format: Format::Text,
+        integer_width: IntegerWidth::I32,
+    };
+
+    let mut args = args[1..].iter().peekable();
+    if args.peek().is_some_and(|arg| arg.as_str() == "interpret") {
+        options.command = Command::Interpret;
+        args.next();
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--entry" => match args.next() {
+                Some(entry) => options.entry = entry,
+                None => return Err("A opção --entry exige o nome de uma função.".to_string()),
+            },
+            "--emit" => match args.next().map(|name| (name, Emit::from_str(name))) {
+                Some((_, Some(format))) => options.emit = Some(format),
+                Some((name, None)) => {
+                    return Err(format!("Formato de --emit desconhecido: {}", name))
+                }
+                None => {
+                    return Err(
+                        "A opção --emit exige um formato: tac, bytecode, asm, c ou wat."
+                            .to_string(),
+                    )
+                }
+            },
+            "--format" => match args.next().map(String::as_str) {
+                Some("text") => options.format = Format::Text,
+                Some("json") => options.format = Format::Json,
+                Some(name) => return Err(format!("Formato de --format desconhecido: {}", name)),
+                None => return Err("A opção --format exige um formato: text ou json.".to_string()),
+            },
+            "--int-width" => match args.next().map(String::as_str) {
+                Some("32") => options.integer_width = IntegerWidth::I32,
+                Some("64") => options.integer_width = IntegerWidth::I64,
+                Some("big") => options.integer_width = IntegerWidth::Arbitrary,
+                Some(width) => {
+                    return Err(format!("Largura de --int-width desconhecida: {}", width))
+                }
+                None => {
+                    return Err("A opção --int-width exige uma largura: 32, 64 ou big.".to_string())
+                }
+            },
+            "--vm" => options.vm = true,
+            "--grammar-report" => options.grammar_report = true,
+            "--trace" => options.trace = true,
+            "--dump" => options.dump = true,
+            "--compat-ne" => options.compat_not_equal = true,
+            "--grammar" => match args.next() {
+                Some(path) => options.grammar_path = Some(path),
+                None => return Err("A opção --grammar exige o caminho de um arquivo.".to_string()),
+            },
+            flag if flag.starts_with("--") => {
+                return Err(format!("Opção desconhecida: {}", flag));
+            }
+            path if options.file_path.is_none() => options.file_path = Some(path),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    if let Format::Json = options.format {
+        if matches!(options.command, Command::Interpret)
+            || options.emit.is_some()
+            || options.trace
+            || options.dump
+            || options.grammar_report
+        {
+            return Err(
+                "A opção --format json não pode ser usada com interpret, --emit, --trace, --dump ou --grammar-report."
+                    .to_string(),
+            );
+        }
+    }
+
+    Ok(options)
+}
+
+/// Lê, valida e retorna a gramática do arquivo `path`, encerrando o programa se ela for inválida.
+fn load_grammar(path: &str) -> Grammar {
+    let source = fs::read_to_string(path).expect("Erro ao ler arquivo da gramática");
+
+    let renderer = renderer(&source, path);
+
+    match parse_grammar(&source) {
+        Ok((grammar, warnings)) => {
+            for warning in &warnings {
+                eprintln!("{}", renderer.render(warning));
+            }
+            grammar
+        }
+        Err(diagnostics) => {
+            eprintln!("A gramática {} é inválida:", path);
+            for diagnostic in &diagnostics {
+                eprintln!("{}", renderer.render(diagnostic));
+            }
             std::process::exit(1);
         }
     }
 }
 
-fn parse_args(args: &[String]) -> Result<&str, &str> {
-    if args.len() != 2 {
-        return Err("O caminho do arquivo de entrada deve ser fornecido como argumento. Por exemplo: cargo run entrada.txt");
+/// Renderizador dos diagnósticos de `source`. A saída de erro só é colorida em um terminal
+/// e se a variável de ambiente `NO_COLOR` não estiver definida.
+fn renderer<'a>(source: &'a str, path: &'a str) -> Renderer<'a> {
+    let color = std::io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none();
+    Renderer::new(source, path, color)
+}
+
+/// Imprime os conflitos LL(1) da gramática e como cada um é resolvido.
+fn print_grammar_report(grammar: &Grammar) {
+    let conflicts = grammar.conflicts();
+    if conflicts.is_empty() {
+        println!("A gramática é LL(1): nenhum conflito encontrado.");
+        return;
+    }
+
+    println!("A gramática não é LL(1): {} conflito(s).", conflicts.len());
+    println!(
+        "Política de resolução: regras de lookahead estendido, preferências declaradas e, por fim, a ordem de declaração.\n"
+    );
+    for conflict in &conflicts {
+        println!("{}\n", conflict);
     }
-    let file_path = &args[1];
-    Ok(file_path)
 }
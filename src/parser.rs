@@ -1,120 +1,549 @@
-/// Realiza a análise sintática de uma sequência de tokens utilizando uma tabela de transição LL(1).
+/// Analisador sintático preditivo para a linguagem LSI-2024-2.
+///
+/// O parser é guiado pela tabela de transição LL(1) e registra a derivação em uma
+/// árvore sintática concreta (`ParseTree`), que depois é convertida na árvore
+/// sintática abstrata (`ast::Program`).
+use std::fmt;
+
+use crate::ast::{self, Program};
+use crate::diagnostic::{Diagnostic, Suggestion};
+use crate::grammar::{ParseTable, Production, END_MARKER};
+use crate::ll1_table::ll1_transition_table;
+use crate::token::{Span, Terminal, Token};
+
+/// Nó da árvore de derivação.
+/// Não terminais possuem filhos (vazios quando derivam ε) e terminais apontam para o token casado.
+#[derive(Debug)]
+pub struct ParseNode {
+    pub symbol: String,       // O símbolo da gramática representado pelo nó
+    pub children: Vec<usize>, // Índices dos filhos em `ParseTree::nodes`
+    pub token: Option<usize>, // Índice do token casado em `ParseTree::tokens`
+}
+
+/// Erro sintático encontrado durante a análise.
+#[derive(Debug, Clone)]
+pub struct SyntaxError {
+    pub message: String,                // Descrição do erro
+    pub span: Span,                     // Posição do token onde o erro foi detectado
+    pub expected: Vec<String>,          // Símbolos esperados naquele ponto da derivação
+    pub found: Option<String>,          // Lexema encontrado (`None` no fim da entrada)
+    pub suggestion: Option<Suggestion>, // Correção provável, como uma `,` esquecida
+}
+
+impl SyntaxError {
+    /// Converte o erro em um diagnóstico, com o token encontrado destacado.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let label = match &self.found {
+            Some(found) => format!("`{}` inesperado", found),
+            None => "fim da entrada".to_string(),
+        };
+        let mut diagnostic = Diagnostic::error(self.message.clone(), self.span)
+            .with_code("E0100")
+            .with_label(label);
+        if let Some(suggestion) = &self.suggestion {
+            diagnostic.suggestions.push(suggestion.clone());
+        }
+        match self.found {
+            Some(_) => diagnostic,
+            None => diagnostic.with_note("o arquivo terminou antes do fim do programa"),
+        }
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Erro sintático na linha {}, coluna {}: {}",
+            self.span.line, self.span.column, self.message
+        )
+    }
+}
+
+/// Árvore de derivação produzida pela análise preditiva.
+/// A raiz é sempre o nó de índice 0, correspondente ao símbolo inicial da gramática.
+#[derive(Debug)]
+pub struct ParseTree {
+    pub nodes: Vec<ParseNode>,
+    pub tokens: Vec<Token>,
+}
+
+impl ParseTree {
+    fn add_node(&mut self, symbol: &str) -> usize {
+        self.nodes.push(ParseNode {
+            symbol: symbol.to_string(),
+            children: vec![],
+            token: None,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Retorna o nó de índice `index`.
+    pub fn node(&self, index: usize) -> &ParseNode {
+        &self.nodes[index]
+    }
+
+    /// Retorna o token casado pelo nó terminal de índice `index`.
+    pub fn token(&self, index: usize) -> Option<&Token> {
+        self.nodes[index].token.map(|token| &self.tokens[token])
+    }
+}
+
+impl fmt::Display for ParseTree {
+    /// Imprime a árvore com um nó por linha, indentado pela profundidade.
+    /// Terminais são acompanhados do lexema casado.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut pending = vec![(0, 0)];
+        while let Some((node, depth)) = pending.pop() {
+            let indent = "  ".repeat(depth);
+            let current = &self.nodes[node];
+            match self.token(node) {
+                Some(token) => writeln!(f, "{}{} `{}`", indent, current.symbol, token.lexeme)?,
+                None if current.children.is_empty() => {
+                    writeln!(f, "{}{} -> ε", indent, current.symbol)?
+                }
+                None => writeln!(f, "{}{}", indent, current.symbol)?,
+            }
+            pending.extend(
+                current
+                    .children
+                    .iter()
+                    .rev()
+                    .map(|&child| (child, depth + 1)),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Evento da análise sintática, notificado a um `ParseObserver` a cada passo do parser.
+#[derive(Debug, Clone, Copy)]
+pub enum ParseEvent<'a> {
+    /// O não terminal no topo da pilha foi expandido pela produção escolhida na tabela.
+    Expand {
+        production: &'a Production,
+        token: Option<&'a Token>, // Token atual (`None` no fim da entrada)
+    },
+    /// O terminal no topo da pilha foi casado com o token atual.
+    Match { terminal: &'a str, token: &'a Token },
+    /// Um erro sintático foi registrado.
+    Error(&'a SyntaxError),
+    /// Recuperação: o símbolo no topo da pilha foi descartado.
+    Pop { symbol: &'a str },
+    /// Recuperação: o token atual foi descartado.
+    Skip { token: &'a Token },
+    /// Recuperação: a análise do não terminal continua a partir do token atual.
+    Resume {
+        nonterminal: &'a str,
+        token: &'a Token,
+    },
+}
+
+/// Observador dos passos do parser, usado para rastrear a análise sem acoplar o parser à saída.
+pub trait ParseObserver {
+    fn on_event(&mut self, event: ParseEvent);
+}
+
+/// Observador que ignora todos os eventos. É o padrão de `parse` e `parse_tree`.
+pub struct Quiet;
+
+impl ParseObserver for Quiet {
+    fn on_event(&mut self, _event: ParseEvent) {}
+}
+
+/// Observador que imprime um rastro legível da análise na saída padrão.
+pub struct Trace;
+
+impl ParseObserver for Trace {
+    fn on_event(&mut self, event: ParseEvent) {
+        match event {
+            ParseEvent::Expand { production, token } => match token {
+                Some(token) => println!(
+                    "Aplicando regra: {}  (token atual: `{}`)",
+                    production, token.lexeme
+                ),
+                None => println!("Aplicando regra: {}  (fim da entrada)", production),
+            },
+            ParseEvent::Match { terminal, token } => println!(
+                "Terminal correspondente: {} `{}` na linha {}, coluna {}",
+                terminal, token.lexeme, token.span.line, token.span.column
+            ),
+            ParseEvent::Error(error) => println!("{}", error),
+            ParseEvent::Pop { symbol } => {
+                println!("Recuperação: descartando `{}` da pilha", symbol)
+            }
+            ParseEvent::Skip { token } => {
+                println!("Recuperação: descartando token `{}`", token.lexeme)
+            }
+            ParseEvent::Resume { nonterminal, token } => println!(
+                "Recuperação: retomando `{}` em `{}`",
+                nonterminal, token.lexeme
+            ),
+        }
+    }
+}
+
+/// Realiza a análise sintática de uma sequência de tokens e constrói a árvore sintática abstrata.
 ///
 /// # Parâmetros
 /// - `tokens`: Um vetor de tokens a serem analisados.
 ///
 /// # Retorna
-/// - `Ok(String)`: Uma mensagem indicando que a análise sintática foi bem-sucedida, juntamente com a sequência de correspondências.
-/// - `Err(String)`: Uma mensagem de erro indicando que ocorreu um erro sintático.
+/// - `Ok(Program)`: A árvore sintática abstrata do programa.
+/// - `Err(Vec<SyntaxError>)`: Todos os erros sintáticos encontrados na entrada.
+pub fn parse(tokens: Vec<Token>) -> Result<Program, Vec<SyntaxError>> {
+    parse_with_observer(tokens, &mut Quiet)
+}
+
+/// Igual a `parse`, notificando cada passo da análise a `observer`.
+pub fn parse_with_observer(
+    tokens: Vec<Token>,
+    observer: &mut dyn ParseObserver,
+) -> Result<Program, Vec<SyntaxError>> {
+    let tree = parse_tree_with_observer(tokens, ll1_transition_table(), observer)?;
+    Ok(ast::build(&tree))
+}
+
+/// Realiza a análise sintática de uma sequência de tokens utilizando uma tabela de transição LL(1).
 ///
-/// # Erros
-/// - Retorna um erro se houver tokens inesperados restantes após o processamento.
-/// - Retorna um erro se um não terminal não puder ser resolvido.
-use crate::ll1_table::ll1_transition_table;
-use crate::token::{Terminal, Token};
+/// Ao encontrar um erro, o parser se recupera em modo pânico e continua a análise,
+/// de forma que uma única execução reporte todos os erros da entrada:
+/// - Se o topo da pilha é um terminal diferente do token atual, o terminal é descartado
+///   (como se tivesse sido inserido).
+/// - Se o topo da pilha é um não terminal sem entrada na tabela para o token atual, os tokens
+///   são descartados até que apareça um token que inicie o não terminal (a análise continua
+///   com ele) ou um token do conjunto de sincronização: o FOLLOW do não terminal, `;` e `}`
+///   (o não terminal é descartado).
+///
+/// # Parâmetros
+/// - `tokens`: Um vetor de tokens a serem analisados.
+///
+/// # Retorna
+/// - `Ok(ParseTree)`: A árvore de derivação correspondente à entrada.
+/// - `Err(Vec<SyntaxError>)`: Os erros sintáticos encontrados, na ordem em que aparecem.
+pub fn parse_tree(tokens: Vec<Token>) -> Result<ParseTree, Vec<SyntaxError>> {
+    parse_tree_with(tokens, ll1_transition_table())
+}
 
-pub fn parse(tokens: Vec<Token>) -> Result<String, String> {
-    let table = ll1_transition_table();
+/// Realiza a análise sintática de uma sequência de tokens com a tabela de uma gramática qualquer,
+/// como as carregadas de arquivo com `bnf::parse_grammar`. A recuperação de erros segue
+/// as mesmas regras de `parse_tree`.
+pub fn parse_tree_with(
+    tokens: Vec<Token>,
+    table: &ParseTable,
+) -> Result<ParseTree, Vec<SyntaxError>> {
+    parse_tree_with_observer(tokens, table, &mut Quiet)
+}
 
-    let mut stack: Vec<&str> = vec!["$", "MAIN"];
+/// Igual a `parse_tree_with`, notificando cada passo da análise a `observer`.
+pub fn parse_tree_with_observer(
+    tokens: Vec<Token>,
+    table: &ParseTable,
+    observer: &mut dyn ParseObserver,
+) -> Result<ParseTree, Vec<SyntaxError>> {
+    let depth = table.lookahead_depth();
+    let start = table.grammar.start.as_str();
+
+    let mut tree = ParseTree {
+        nodes: vec![],
+        tokens,
+    };
+    let root = tree.add_node(start);
+
+    // Cada entrada da pilha guarda o símbolo e o nó da árvore que ele representa
+    let mut stack: Vec<(&str, usize)> = vec![(END_MARKER, root), (start, root)];
     let mut index = 0;
-    let mut matches: Vec<String> = vec![]; // Para armazenar a sequência de "matches"
-
-    while let Some(top) = stack.pop() {
-        println!();
-        println!("Pilha: {:?}", stack);
-        println!("Topo da pilha: {}", top);
-
-        if top == "$" {
-            if index == tokens.len() {
-                // Sucesso: retorna a sequência de "matches"
-                let match_sequence = matches.join(" / ");
-                return Ok(format!(
-                    "\nAnálise sintática bem-sucedida.\nSequência de correspondências: \n[{}].",
-                    match_sequence
-                ));
-            } else {
-                return Err("Tokens inesperados restantes.".to_string());
+    let mut errors: Vec<SyntaxError> = vec![];
+    // Índice do token do último erro, para não reportar erros em cascata no mesmo token
+    let mut last_error: Option<usize> = None;
+
+    while let Some((top, node)) = stack.pop() {
+        // Próximos terminais da entrada, completados com `$` no fim
+        let window: Vec<&str> = (index..index + depth)
+            .map(|i| {
+                tree.tokens
+                    .get(i)
+                    .map_or(END_MARKER, |token| token.terminal_name())
+            })
+            .collect();
+        let lookahead = window[0];
+
+        if top == END_MARKER {
+            if lookahead != END_MARKER {
+                let error = syntax_error(
+                    &tree.tokens,
+                    index,
+                    "tokens inesperados restantes após o fim do programa".to_string(),
+                    vec![],
+                );
+                report(&mut errors, &mut last_error, observer, index, error);
             }
+            break;
         }
 
-        let current_token = tokens.get(index);
-
-        // Verifica se o final da entrada foi alcançado
-        if current_token.is_none() {
-            if let Some(rule) = table.get(&(top, Terminal::DollarSign)) {
-                println!("Aplicando transição epsilon para {}", top);
-                if *rule != "ε" {
-                    stack.extend(rule.split_whitespace().rev());
-                }
-                continue;
+        if !table.grammar.is_nonterminal(top) {
+            if top == lookahead {
+                // Casamento bem-sucedido
+                observer.on_event(ParseEvent::Match {
+                    terminal: top,
+                    token: &tree.tokens[index],
+                });
+                tree.nodes[node].token = Some(index);
+                index += 1;
             } else {
-                return Err(format!(
-                    "Erro sintático: não terminal `{}` restante na pilha.",
-                    top
-                ));
+                // Erro sintático: descarta o terminal esperado
+                let error = expected_error(table, top, &[top], &tree.tokens, index);
+                report(&mut errors, &mut last_error, observer, index, error);
+                observer.on_event(ParseEvent::Pop { symbol: top });
             }
-        }
+        } else if let Some(rule) = table.select(top, &window) {
+            observer.on_event(ParseEvent::Expand {
+                production: rule,
+                token: tree.tokens.get(index),
+            });
+            expand(&mut tree, &mut stack, node, rule);
+        } else {
+            // Erro sintático: modo pânico até um token de sincronização
+            let expected = table.expected(top);
+            let error = expected_error(table, top, &expected, &tree.tokens, index);
+            report(&mut errors, &mut last_error, observer, index, error);
 
-        let current_token = current_token.unwrap();
-        println!("Token atual: {:?}", current_token);
+            let mut sync = table.follow_of(top);
+            sync.extend([Terminal::Semicolon.as_str(), Terminal::RightBrace.as_str()]);
 
-        // Casos especiais para `IFSTMT'`
-        if top == "IFSTMT'" {
-            if current_token.terminal == Terminal::Else {
-                println!("Tratando ramo `else` para IFSTMT'");
-                stack.extend(vec!["else", "STMT"].iter().rev());
-            } else {
-                println!("Aplicando transição epsilon para IFSTMT' (sem ramo else)");
+            let mut resumed = false;
+            while let Some(token) = tree.tokens.get(index) {
+                if table.get(top, token.terminal_name()).is_some() {
+                    observer.on_event(ParseEvent::Resume {
+                        nonterminal: top,
+                        token,
+                    });
+                    stack.push((top, node));
+                    resumed = true;
+                    break;
+                }
+                if sync.contains(token.terminal_name()) {
+                    break;
+                }
+                observer.on_event(ParseEvent::Skip { token });
+                index += 1;
+            }
+            if !resumed {
+                observer.on_event(ParseEvent::Pop { symbol: top });
             }
-            continue;
         }
+    }
+
+    if errors.is_empty() {
+        Ok(tree)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Registra um erro sintático no token `index`, a menos que o último erro tenha sido no mesmo token.
+fn report(
+    errors: &mut Vec<SyntaxError>,
+    last_error: &mut Option<usize>,
+    observer: &mut dyn ParseObserver,
+    index: usize,
+    error: SyntaxError,
+) {
+    if *last_error == Some(index) {
+        return;
+    }
+    *last_error = Some(index);
 
-        // Casos especiais para `ATRIBST'`
-        if top == "ATRIBST'" {
-            if let Some(next_token) = tokens.get(index + 1) {
-                if next_token.terminal == Terminal::LeftParen {
-                    println!("Tratando chamada de função para ATRIBST'");
-                    stack.extend(vec!["FCALL"].iter().rev());
+    errors.push(error);
+    observer.on_event(ParseEvent::Error(errors.last().unwrap()));
+}
+
+/// Erro sintático no token `index`, sem sugestão de correção.
+fn syntax_error(
+    tokens: &[Token],
+    index: usize,
+    message: String,
+    expected: Vec<String>,
+) -> SyntaxError {
+    SyntaxError {
+        message,
+        span: span_at(tokens, index),
+        expected,
+        found: tokens.get(index).map(|token| token.lexeme.clone()),
+        suggestion: None,
+    }
+}
+
+/// Erro de um token que não é aceito com `top` no topo da pilha, listando os terminais
+/// `expected` e situando o erro com a descrição de `top` declarada na gramática (`%describe`).
+///
+/// # Retorna
+/// Um erro como "esperado `,` ou `;` após a lista de variáveis, encontrado `b`", com uma
+/// sugestão de correção quando o erro tem uma causa provável.
+fn expected_error(
+    table: &ParseTable,
+    top: &str,
+    expected: &[&str],
+    tokens: &[Token],
+    index: usize,
+) -> SyntaxError {
+    let context = match table.grammar.descriptions.get(top) {
+        Some(description) => format!(" {}", description),
+        None => String::new(),
+    };
+    let message = format!(
+        "esperado {}{}, encontrado {}",
+        one_of(expected),
+        context,
+        describe(tokens, index)
+    );
+    let mut error = syntax_error(
+        tokens,
+        index,
+        message,
+        expected
+            .iter()
+            .map(|terminal| terminal.to_string())
+            .collect(),
+    );
+    error.suggestion = hint(table, top, expected, tokens, index);
+    error
+}
+
+/// Lista os terminais esperados: "`;`", "`,` ou `;`" ou "um de `a`, `b` ou `c`".
+fn one_of(expected: &[&str]) -> String {
+    let names: Vec<String> = expected
+        .iter()
+        .map(|&terminal| match terminal {
+            END_MARKER => "o fim da entrada".to_string(),
+            terminal => format!("`{}`", terminal),
+        })
+        .collect();
+    match names.as_slice() {
+        [] => "outro token".to_string(),
+        [name] => name.clone(),
+        [first, second] => format!("{} ou {}", first, second),
+        [init @ .., last] => format!("um de {} ou {}", init.join(", "), last),
+    }
+}
+
+/// Sugestão para as causas mais comuns de erro:
+/// - Uma `,` esquecida entre dois itens de uma lista, como em `verificaMaior(Lado1 Lado2)`:
+///   `top` aceitaria `,` e, depois dela, o token encontrado.
+/// - Um `;` esquecido no fim da linha: `;` fecharia o que veio antes (não é o início de um
+///   comando vazio) e o token encontrado está em outra linha (ou a entrada terminou).
+fn hint(
+    table: &ParseTable,
+    top: &str,
+    expected: &[&str],
+    tokens: &[Token],
+    index: usize,
+) -> Option<Suggestion> {
+    let previous = tokens.get(index.checked_sub(1)?)?;
+    let insert = |replacement: &str, message: String| Suggestion {
+        message,
+        span: span_after(previous),
+        replacement: replacement.to_string(),
+    };
+    let comma = Terminal::Comma.as_str();
+    let semicolon = Terminal::Semicolon.as_str();
+    let closes = expected.contains(&semicolon)
+        && (top == semicolon
+            || table
+                .get(top, semicolon)
+                .is_some_and(|rule| rule.rhs.is_empty()));
+
+    match tokens.get(index) {
+        Some(found) => {
+            let terminal = found.terminal_name();
+            let after_comma = table
+                .get(top, comma)
+                .and_then(|rule| match rule.rhs.as_slice() {
+                    [first, next, ..] if first == comma => Some(next.as_str()),
+                    _ => None,
+                });
+            let accepts = |symbol: &str| {
+                if table.grammar.is_nonterminal(symbol) {
+                    table.get(symbol, terminal).is_some()
                 } else {
-                    println!("Tratando expressão para ATRIBST'");
-                    stack.extend(vec!["EXPR"].iter().rev());
+                    symbol == terminal
                 }
+            };
+            if after_comma.is_some_and(accepts) {
+                Some(insert(
+                    comma,
+                    format!(
+                        "adicione `,` entre `{}` e `{}`",
+                        previous.lexeme, found.lexeme
+                    ),
+                ))
+            } else if closes && found.span.line > previous.span.line {
+                Some(insert(
+                    semicolon,
+                    format!("adicione `;` após `{}`", previous.lexeme),
+                ))
             } else {
-                return Err("Fim inesperado da entrada ao resolver ATRIBST'.".to_string());
+                None
             }
-            continue;
         }
+        None if closes => Some(insert(
+            semicolon,
+            format!("adicione `;` após `{}`", previous.lexeme),
+        )),
+        None => None,
+    }
+}
 
-        if let Some(rule) = table.get(&(top, current_token.terminal.clone())) {
-            println!("Aplicando regra: {} -> {}", top, rule);
+/// Descreve o token `index` para mensagens de erro.
+fn describe(tokens: &[Token], index: usize) -> String {
+    match tokens.get(index) {
+        Some(token) => format!("`{}`", token.lexeme),
+        None => "o fim da entrada".to_string(),
+    }
+}
 
-            if *rule != "ε" {
-                stack.extend(rule.split_whitespace().rev());
-            }
-        } else if top == current_token.terminal.as_str() {
-            // Casamento bem-sucedido
-            println!("Terminal correspondente: {}", top);
-            matches.push(top.to_string());
-            index += 1;
-        } else {
-            // Erro sintático
-            if current_token.terminal.as_str() != top {
-                return Err(format!(
-                    "Erro sintático: esperado `{}`, encontrado `{}` no token {}.",
-                    top,
-                    current_token.terminal.as_str(),
-                    index + 1
-                ));
-            } else {
-                return Err(format!(
-                    "Erro sintático: não terminal `{}` não encontrado na tabela de análise.",
-                    top
-                ));
-            }
-        }
+/// Posição do token `index`, ou a posição logo após o último token no fim da entrada.
+fn span_at(tokens: &[Token], index: usize) -> Span {
+    match (tokens.get(index), tokens.last()) {
+        (Some(token), _) => token.span,
+        (None, Some(last)) => span_after(last),
+        (None, None) => Span {
+            line: 1,
+            column: 1,
+            ..Span::default()
+        },
+    }
+}
+
+/// Posição vazia logo após o token, onde uma correção pode inserir texto.
+fn span_after(token: &Token) -> Span {
+    Span {
+        start: token.span.end,
+        end: token.span.end,
+        line: token.span.line,
+        column: token.span.column + token.lexeme.chars().count(),
     }
+}
 
-    println!("Análise sintática concluída.");
-    Ok("Análise sintática concluída.".to_string())
+/// Aplica a produção `rule` ao nó `node`, criando um filho para cada símbolo do lado direito
+/// e empilhando-os em ordem reversa.
+fn expand<'a>(
+    tree: &mut ParseTree,
+    stack: &mut Vec<(&'a str, usize)>,
+    node: usize,
+    rule: &'a Production,
+) {
+    let children: Vec<usize> = rule
+        .rhs
+        .iter()
+        .map(|symbol| tree.add_node(symbol))
+        .collect();
+    stack.extend(
+        rule.rhs
+            .iter()
+            .map(String::as_str)
+            .zip(children.iter().copied())
+            .rev(),
+    );
+    tree.nodes[node].children = children;
 }
@@ -0,0 +1,338 @@
+/// Análise semântica da linguagem LSI-2024-2.
+///
+/// Percorre a árvore sintática abstrata com uma tabela de símbolos com escopos aninhados:
+/// os parâmetros e o corpo de uma função compartilham um escopo, e cada bloco `{ ... }`
+/// (assim como cada ramo de um `if`) abre um novo escopo. São reportados:
+/// - o uso de variáveis não declaradas (erro);
+/// - a redeclaração de uma variável no mesmo escopo (erro);
+/// - declarações que ocultam uma variável de um escopo externo (aviso);
+/// - literais inteiros que não cabem em 32 bits, lidos com `LexerOptions::integer_width`
+///   maior (erro), já que a execução e a geração de código usam inteiros de 32 bits.
+///
+/// As assinaturas de todas as funções são coletadas antes do percurso, de forma que uma função
+/// possa ser chamada antes da sua definição. São reportadas como erros as chamadas a funções
+/// não definidas, chamadas com o número errado de argumentos e funções definidas mais de uma vez.
+use std::collections::HashMap;
+
+use crate::ast::{Expr, FuncCall, FuncDef, Ident, Program, Stmt};
+use crate::diagnostic::Diagnostic;
+use crate::token::Span;
+
+/// Tipo de um símbolo declarado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Parameter, // Parâmetro de função
+    Variable,  // Variável declarada com `int`
+}
+
+/// Símbolo declarado em um escopo.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,     // Nome do símbolo
+    pub kind: SymbolKind, // Tipo do símbolo
+    pub span: Span,       // Posição da declaração
+}
+
+/// Assinatura de uma função definida com `def`.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub name: String, // Nome da função
+    pub arity: usize, // Número de parâmetros
+    pub span: Span,   // Posição do nome na definição
+}
+
+/// Coleta a assinatura de cada função do programa, na ordem de definição.
+/// Uma função definida mais de uma vez gera um erro, e vale a primeira definição.
+pub fn signatures(program: &Program) -> (HashMap<String, Signature>, Vec<Diagnostic>) {
+    let mut signatures: HashMap<String, Signature> = HashMap::new();
+    let mut diagnostics = vec![];
+
+    for function in &program.functions {
+        let name = &function.name;
+        if let Some(previous) = signatures.get(&name.name) {
+            diagnostics.push(
+                Diagnostic::error(
+                    format!(
+                        "a função `{}` já foi definida na linha {}, coluna {}",
+                        name.name, previous.span.line, previous.span.column
+                    ),
+                    name.span,
+                )
+                .with_code("E0204")
+                .with_label("definida novamente aqui")
+                .with_secondary_label(previous.span, "primeira definição"),
+            );
+            continue;
+        }
+        signatures.insert(
+            name.name.clone(),
+            Signature {
+                name: name.name.clone(),
+                arity: function.params.len(),
+                span: name.span,
+            },
+        );
+    }
+
+    (signatures, diagnostics)
+}
+
+/// Tabela de símbolos com escopos aninhados. O último escopo da pilha é o mais interno.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    scopes: Vec<HashMap<String, Symbol>>,
+}
+
+impl SymbolTable {
+    pub fn new() -> SymbolTable {
+        SymbolTable::default()
+    }
+
+    /// Abre um novo escopo, mais interno que os atuais.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    /// Fecha o escopo mais interno, descartando os seus símbolos.
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Declara `symbol` no escopo mais interno.
+    ///
+    /// # Retorna
+    /// - `Ok(())`: O símbolo foi declarado.
+    /// - `Err(&Symbol)`: A declaração anterior de um símbolo com o mesmo nome no mesmo escopo.
+    pub fn declare(&mut self, symbol: Symbol) -> Result<(), &Symbol> {
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("declaração fora de qualquer escopo");
+        if scope.contains_key(&symbol.name) {
+            return Err(&scope[&symbol.name]);
+        }
+        scope.insert(symbol.name.clone(), symbol);
+        Ok(())
+    }
+
+    /// Procura `name` do escopo mais interno para o mais externo.
+    pub fn lookup(&self, name: &str) -> Option<&Symbol> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// Procura `name` apenas nos escopos externos ao mais interno.
+    pub fn lookup_outer(&self, name: &str) -> Option<&Symbol> {
+        let outer = self.scopes.len().saturating_sub(1);
+        self.scopes[..outer]
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+    }
+}
+
+/// Verifica as declarações de variáveis e as chamadas de função do programa.
+///
+/// # Retorna
+/// Os erros e avisos semânticos. As funções duplicadas vêm primeiro; os demais
+/// diagnósticos seguem a ordem em que aparecem no programa.
+pub fn check(program: &Program) -> Vec<Diagnostic> {
+    let (functions, diagnostics) = signatures(program);
+    let mut checker = Checker {
+        symbols: SymbolTable::new(),
+        functions,
+        diagnostics,
+    };
+
+    for function in &program.functions {
+        checker.function(function);
+    }
+    if let Some(statement) = &program.statement {
+        checker.symbols.push_scope();
+        checker.stmt(statement);
+        checker.symbols.pop_scope();
+    }
+
+    checker.diagnostics
+}
+
+/// Estado do percurso da AST.
+struct Checker {
+    symbols: SymbolTable,
+    functions: HashMap<String, Signature>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+    fn function(&mut self, function: &FuncDef) {
+        // Parâmetros e corpo compartilham o mesmo escopo
+        self.symbols.push_scope();
+        for param in &function.params {
+            self.declare(param, SymbolKind::Parameter);
+        }
+        for stmt in &function.body {
+            self.stmt(stmt);
+        }
+        self.symbols.pop_scope();
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::VarDecl(names) => {
+                for name in names {
+                    self.declare(name, SymbolKind::Variable);
+                }
+            }
+            Stmt::Assign { target, value } => {
+                self.use_var(target);
+                self.expr(value);
+            }
+            Stmt::Print(expr) => self.expr(expr),
+            Stmt::Return(value) => {
+                if let Some(name) = value {
+                    self.use_var(name);
+                }
+            }
+            Stmt::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                self.scoped(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.scoped(else_branch);
+                }
+            }
+            Stmt::Block(stmts) => {
+                self.symbols.push_scope();
+                for stmt in stmts {
+                    self.stmt(stmt);
+                }
+                self.symbols.pop_scope();
+            }
+            Stmt::Empty => {}
+        }
+    }
+
+    /// Verifica um ramo de `if` em um escopo próprio, mesmo quando ele não é um bloco.
+    fn scoped(&mut self, stmt: &Stmt) {
+        if let Stmt::Block(_) = stmt {
+            self.stmt(stmt);
+        } else {
+            self.symbols.push_scope();
+            self.stmt(stmt);
+            self.symbols.pop_scope();
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Num { value, span } => {
+                if value.to_i32().is_none() {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            format!(
+                                "literal inteiro `{}` fora do intervalo dos inteiros de 32 bits da LSI-2024-2",
+                                value
+                            ),
+                            *span,
+                        )
+                        .with_code("E0004")
+                        .with_label(format!("maior que {}", i32::MAX))
+                        .with_note(
+                            "a execução e a geração de código usam inteiros de 32 bits, qualquer que seja a largura dos literais",
+                        ),
+                    );
+                }
+            }
+            Expr::Var(name) => self.use_var(name),
+            Expr::Binary { lhs, rhs, .. } | Expr::Relational { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            Expr::Call(call) => {
+                self.call(call);
+                for arg in &call.args {
+                    self.use_var(arg);
+                }
+            }
+        }
+    }
+
+    fn call(&mut self, call: &FuncCall) {
+        let name = &call.name;
+        match self.functions.get(&name.name) {
+            None => self.diagnostics.push(
+                Diagnostic::error(format!("função `{}` não definida", name.name), name.span)
+                    .with_code("E0202")
+                    .with_label("função desconhecida"),
+            ),
+            Some(signature) if signature.arity != call.args.len() => {
+                let message = format!(
+                    "a função `{}` espera {} argumento(s), mas recebeu {} (definida na linha {}, coluna {})",
+                    name.name,
+                    signature.arity,
+                    call.args.len(),
+                    signature.span.line,
+                    signature.span.column
+                );
+                let definition = format!("definida aqui com {} parâmetro(s)", signature.arity);
+                self.diagnostics.push(
+                    Diagnostic::error(message, name.span)
+                        .with_code("E0203")
+                        .with_label(format!("{} argumento(s)", call.args.len()))
+                        .with_secondary_label(signature.span, definition),
+                );
+            }
+            Some(_) => {}
+        }
+    }
+
+    fn declare(&mut self, name: &Ident, kind: SymbolKind) {
+        if let Some(outer) = self.symbols.lookup_outer(&name.name) {
+            self.diagnostics.push(
+                Diagnostic::warning(
+                    format!(
+                        "a declaração de `{}` oculta a declaração da linha {}, coluna {}",
+                        name.name, outer.span.line, outer.span.column
+                    ),
+                    name.span,
+                )
+                .with_code("W0200")
+                .with_secondary_label(outer.span, "declaração ocultada"),
+            );
+        }
+
+        let symbol = Symbol {
+            name: name.name.clone(),
+            kind,
+            span: name.span,
+        };
+        if let Err(previous) = self.symbols.declare(symbol) {
+            let message = format!(
+                "`{}` já foi declarado neste escopo na linha {}, coluna {}",
+                name.name, previous.span.line, previous.span.column
+            );
+            self.diagnostics.push(
+                Diagnostic::error(message, name.span)
+                    .with_code("E0201")
+                    .with_label("declarado novamente aqui")
+                    .with_secondary_label(previous.span, "primeira declaração"),
+            );
+        }
+    }
+
+    fn use_var(&mut self, name: &Ident) {
+        if self.symbols.lookup(&name.name).is_none() {
+            self.diagnostics.push(
+                Diagnostic::error(format!("variável `{}` não declarada", name.name), name.span)
+                    .with_code("E0200")
+                    .with_label("não encontrada neste escopo")
+                    .with_note(format!(
+                        "declare a variável antes do uso: `int {};`",
+                        name.name
+                    )),
+            );
+        }
+    }
+}
@@ -0,0 +1,408 @@
+/// Representação intermediária em código de três endereços (TAC).
+///
+/// Cada função da AST é traduzida para uma lista de instruções com no máximo um operador,
+/// usando temporários (`t1`, `t2`, ...) para os resultados intermediários e rótulos
+/// (`L1`, `L2`, ...) com desvios condicionais para o `if`. Chamadas de função são
+/// sequências de `param` seguidas de `call`.
+///
+/// A tradução segue a semântica do interpretador: variáveis declaradas começam com 0,
+/// toda função termina com `return` (que retorna 0 quando não há valor) e um programa
+/// formado por um único comando vira a função `principal`. Variáveis que ocultam outra
+/// de um escopo externo são renomeadas (`x.1`, `x.2`, ...), de forma que cada nome
+/// corresponda a uma única variável da função. O programa deve ter passado pela análise semântica.
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::ast::{literal_value, BinOp, Expr, FuncDef, Ident, Program, RelOp, Stmt};
+use crate::interpreter::DEFAULT_ENTRY;
+
+/// Operando de uma instrução.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Var(String), // Parâmetro ou variável local
+    Temp(usize), // Temporário gerado na tradução
+    Const(i32),  // Constante inteira
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Var(name) => write!(f, "{}", name),
+            Operand::Temp(index) => write!(f, "t{}", index),
+            Operand::Const(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Instrução de três endereços.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instr {
+    /// `dest = src`
+    Copy { dest: Operand, src: Operand },
+    /// `dest = lhs op rhs`
+    Binary {
+        dest: Operand,
+        op: BinOp,
+        lhs: Operand,
+        rhs: Operand,
+    },
+    /// `dest = lhs op rhs`, com resultado 1 ou 0
+    Relational {
+        dest: Operand,
+        op: RelOp,
+        lhs: Operand,
+        rhs: Operand,
+    },
+    /// `ifFalse lhs op rhs goto L`
+    IfFalse {
+        op: RelOp,
+        lhs: Operand,
+        rhs: Operand,
+        target: usize,
+    },
+    /// `goto L`
+    Goto(usize),
+    /// `L:`
+    Label(usize),
+    /// `param arg`
+    Param(Operand),
+    /// `dest = call function, argc`
+    Call {
+        dest: Operand,
+        function: String,
+        argc: usize,
+    },
+    /// `return` ou `return value`
+    Return(Option<Operand>),
+    /// `print value`
+    Print(Operand),
+}
+
+impl fmt::Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instr::Copy { dest, src } => write!(f, "{} = {}", dest, src),
+            Instr::Binary { dest, op, lhs, rhs } => {
+                write!(f, "{} = {} {} {}", dest, lhs, op.as_str(), rhs)
+            }
+            Instr::Relational { dest, op, lhs, rhs } => {
+                write!(f, "{} = {} {} {}", dest, lhs, op.as_str(), rhs)
+            }
+            Instr::IfFalse {
+                op,
+                lhs,
+                rhs,
+                target,
+            } => write!(
+                f,
+                "ifFalse {} {} {} goto L{}",
+                lhs,
+                op.as_str(),
+                rhs,
+                target
+            ),
+            Instr::Goto(target) => write!(f, "goto L{}", target),
+            Instr::Label(label) => write!(f, "L{}:", label),
+            Instr::Param(arg) => write!(f, "param {}", arg),
+            Instr::Call {
+                dest,
+                function,
+                argc,
+            } => write!(f, "{} = call {}, {}", dest, function, argc),
+            Instr::Return(Some(value)) => write!(f, "return {}", value),
+            Instr::Return(None) => write!(f, "return"),
+            Instr::Print(value) => write!(f, "print {}", value),
+        }
+    }
+}
+
+/// Função traduzida para TAC.
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,        // Nome da função
+    pub params: Vec<String>, // Parâmetros, na ordem da definição
+    pub locals: Vec<String>, // Variáveis locais (já renomeadas), na ordem de declaração
+    pub temps: usize,        // Número de temporários (`t1` a `tN`)
+    pub labels: usize,       // Número de rótulos (`L1` a `LN`)
+    pub body: Vec<Instr>,    // Instruções
+}
+
+/// Programa traduzido para TAC.
+#[derive(Debug, Clone)]
+pub struct TacProgram {
+    pub functions: Vec<Function>,
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "function {}({}):", self.name, self.params.join(", "))?;
+        for instr in &self.body {
+            match instr {
+                Instr::Label(_) => writeln!(f, "{}", instr)?,
+                _ => writeln!(f, "    {}", instr)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TacProgram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, function) in self.functions.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", function)?;
+        }
+        Ok(())
+    }
+}
+
+/// Traduz o programa para código de três endereços.
+///
+/// # Pânico
+/// Entra em pânico se o programa usar uma variável não declarada ou um literal que não cabe em
+/// 32 bits; o programa deve ter passado pela análise semântica (`semantic::check`) sem erros.
+pub fn lower(program: &Program) -> TacProgram {
+    let mut functions: Vec<Function> = program.functions.iter().map(lower_function).collect();
+
+    if let Some(statement) = &program.statement {
+        let mut builder = Builder::new(&[]);
+        builder.stmt(statement);
+        functions.push(builder.finish(DEFAULT_ENTRY));
+    }
+
+    TacProgram { functions }
+}
+
+fn lower_function(function: &FuncDef) -> Function {
+    let mut builder = Builder::new(&function.params);
+    for stmt in &function.body {
+        builder.stmt(stmt);
+    }
+    builder.finish(&function.name.name)
+}
+
+/// Estado da tradução de uma função.
+struct Builder {
+    params: Vec<String>,
+    locals: Vec<String>,
+    scopes: Vec<HashMap<String, String>>, // Nome no código fonte -> nome na TAC, por escopo
+    versions: HashMap<String, usize>,     // Quantas vezes cada nome já foi declarado na função
+    temps: usize,
+    labels: usize,
+    body: Vec<Instr>,
+}
+
+impl Builder {
+    fn new(params: &[Ident]) -> Builder {
+        let mut builder = Builder {
+            params: vec![],
+            locals: vec![],
+            scopes: vec![HashMap::new()],
+            versions: HashMap::new(),
+            temps: 0,
+            labels: 0,
+            body: vec![],
+        };
+        for param in params {
+            let name = builder.declare(&param.name);
+            builder.params.push(name);
+        }
+        builder
+    }
+
+    /// Garante que a função termine com `return` e retorna a função traduzida.
+    fn finish(mut self, name: &str) -> Function {
+        if !matches!(self.body.last(), Some(Instr::Return(_))) {
+            self.body.push(Instr::Return(None));
+        }
+        Function {
+            name: name.to_string(),
+            params: self.params,
+            locals: self.locals,
+            temps: self.temps,
+            labels: self.labels,
+            body: self.body,
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::VarDecl(names) => {
+                for name in names {
+                    let name = self.declare(&name.name);
+                    self.locals.push(name.clone());
+                    self.body.push(Instr::Copy {
+                        dest: Operand::Var(name),
+                        src: Operand::Const(0),
+                    });
+                }
+            }
+            Stmt::Assign { target, value } => {
+                let dest = self.var(target);
+                self.expr_to(value, Some(dest));
+            }
+            Stmt::Print(expr) => {
+                let value = self.expr(expr);
+                self.body.push(Instr::Print(value));
+            }
+            Stmt::Return(value) => {
+                let value = value.as_ref().map(|name| self.var(name));
+                self.body.push(Instr::Return(value));
+            }
+            Stmt::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                let (op, lhs, rhs) = self.condition(cond);
+                let else_label = self.new_label();
+                self.body.push(Instr::IfFalse {
+                    op,
+                    lhs,
+                    rhs,
+                    target: else_label,
+                });
+                self.scoped(then_branch);
+
+                match else_branch {
+                    Some(else_branch) => {
+                        let end_label = self.new_label();
+                        self.body.push(Instr::Goto(end_label));
+                        self.body.push(Instr::Label(else_label));
+                        self.scoped(else_branch);
+                        self.body.push(Instr::Label(end_label));
+                    }
+                    None => self.body.push(Instr::Label(else_label)),
+                }
+            }
+            Stmt::Block(stmts) => {
+                self.scopes.push(HashMap::new());
+                for stmt in stmts {
+                    self.stmt(stmt);
+                }
+                self.scopes.pop();
+            }
+            Stmt::Empty => {}
+        }
+    }
+
+    /// Traduz um ramo de `if` em um escopo próprio, como na análise semântica.
+    fn scoped(&mut self, stmt: &Stmt) {
+        self.scopes.push(HashMap::new());
+        self.stmt(stmt);
+        self.scopes.pop();
+    }
+
+    /// Traduz a condição de um `if` para o desvio `ifFalse`.
+    /// Condições que não são relacionais são comparadas com zero.
+    fn condition(&mut self, cond: &Expr) -> (RelOp, Operand, Operand) {
+        match cond {
+            Expr::Relational { op, lhs, rhs } => {
+                let lhs = self.expr(lhs);
+                let rhs = self.expr(rhs);
+                (*op, lhs, rhs)
+            }
+            _ => (RelOp::Ne, self.expr(cond), Operand::Const(0)),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Operand {
+        self.expr_to(expr, None)
+    }
+
+    /// Traduz `expr` guardando o resultado em `dest`, ou em um novo temporário se `dest`
+    /// for `None` e a expressão não for uma constante ou variável.
+    fn expr_to(&mut self, expr: &Expr, dest: Option<Operand>) -> Operand {
+        let value = match expr {
+            Expr::Num { value, .. } => Operand::Const(literal_value(value)),
+            Expr::Var(name) => self.var(name),
+            Expr::Binary { op, lhs, rhs } => {
+                let lhs = self.expr(lhs);
+                let rhs = self.expr(rhs);
+                let dest = dest.unwrap_or_else(|| self.new_temp());
+                self.body.push(Instr::Binary {
+                    dest: dest.clone(),
+                    op: *op,
+                    lhs,
+                    rhs,
+                });
+                return dest;
+            }
+            Expr::Relational { op, lhs, rhs } => {
+                let lhs = self.expr(lhs);
+                let rhs = self.expr(rhs);
+                let dest = dest.unwrap_or_else(|| self.new_temp());
+                self.body.push(Instr::Relational {
+                    dest: dest.clone(),
+                    op: *op,
+                    lhs,
+                    rhs,
+                });
+                return dest;
+            }
+            Expr::Call(call) => {
+                for arg in &call.args {
+                    let arg = self.var(arg);
+                    self.body.push(Instr::Param(arg));
+                }
+                let dest = dest.unwrap_or_else(|| self.new_temp());
+                self.body.push(Instr::Call {
+                    dest: dest.clone(),
+                    function: call.name.name.clone(),
+                    argc: call.args.len(),
+                });
+                return dest;
+            }
+        };
+
+        match dest {
+            Some(dest) => {
+                self.body.push(Instr::Copy {
+                    dest: dest.clone(),
+                    src: value,
+                });
+                dest
+            }
+            None => value,
+        }
+    }
+
+    /// Declara `name` no escopo atual e retorna o seu nome na TAC.
+    fn declare(&mut self, name: &str) -> String {
+        let version = self.versions.entry(name.to_string()).or_insert(0);
+        let unique = match *version {
+            0 => name.to_string(),
+            n => format!("{}.{}", name, n),
+        };
+        *version += 1;
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), unique.clone());
+        unique
+    }
+
+    /// Nome na TAC da variável `name` visível no escopo atual.
+    /// Entra em pânico se ela não foi declarada (ver `lower`).
+    fn var(&self, name: &Ident) -> Operand {
+        let unique = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.name))
+            .unwrap_or_else(|| panic!("variável `{}` não declarada", name.name));
+        Operand::Var(unique.clone())
+    }
+
+    fn new_temp(&mut self) -> Operand {
+        self.temps += 1;
+        Operand::Temp(self.temps)
+    }
+
+    fn new_label(&mut self) -> usize {
+        self.labels += 1;
+        self.labels
+    }
+}
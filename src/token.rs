@@ -1,5 +1,5 @@
-#[derive(Debug)]
 /// Esse arquivo contém os tipos, structs e implementações usadas para representar tokens e terminais.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TokenType {
     Id,
     Int,
@@ -41,8 +41,9 @@ pub enum Terminal {
     Equal,        // ==
     NotEqual,     // <>
     Else,         // else
-    DollarSign,   // $
-    Epsilon,      // ε
+    Keyword, // Palavra reservada que não faz parte da LSI-2024-2 (gramáticas carregadas de arquivo)
+    DollarSign, // $
+    Epsilon, // ε
 }
 
 impl Terminal {
@@ -69,6 +70,7 @@ impl Terminal {
             Terminal::Equal => "==",
             Terminal::NotEqual => "<>",
             Terminal::Else => "else",
+            Terminal::Keyword => "keyword",
             Terminal::DollarSign => "$",
             Terminal::Epsilon => "ε",
             Terminal::Plus => "+",
@@ -78,6 +80,7 @@ impl Terminal {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Terminal> {
         match s {
             "int" => Some(Terminal::Int),
@@ -116,17 +119,105 @@ impl Terminal {
 #[derive(Debug)]
 pub enum TokenValue {
     Lexeme(String),       // Lexema geral (e.g. nome de variável)
-    Number(i32),          // Valor numérico
+    Number(Number),       // Valor numérico
     RelopLabel(String),   // Operadores relacionais (e.g., <, >)
     ArithOpLabel(String), // Operadores aritméticos (e.g., +, -)
 }
 
+/// Valor de um literal inteiro, na largura escolhida em `LexerOptions::integer_width`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Number {
+    I32(i32),
+    I64(i64),
+    Big(String), // Dígitos decimais, sem zeros à esquerda
+}
+
+impl Number {
+    /// O valor como inteiro de 32 bits, se couber.
+    pub fn to_i32(&self) -> Option<i32> {
+        match self {
+            Number::I32(value) => Some(*value),
+            Number::I64(value) => i32::try_from(*value).ok(),
+            Number::Big(digits) => digits.parse().ok(),
+        }
+    }
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Number::I32(value) => write!(f, "{}", value),
+            Number::I64(value) => write!(f, "{}", value),
+            Number::Big(digits) => f.write_str(digits),
+        }
+    }
+}
+
+/// Posição de um trecho do código fonte.
+/// Os offsets são em bytes e as linhas e colunas começam em 1.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,  // Offset do primeiro byte do trecho
+    pub end: usize,    // Offset logo após o último byte do trecho
+    pub line: usize,   // Linha onde o trecho começa
+    pub column: usize, // Coluna onde o trecho começa
+}
+
+impl std::fmt::Debug for Span {
+    // Formato compacto, já que spans aparecem em todos os tokens e nós da AST
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{} ({}..{})",
+            self.line, self.column, self.start, self.end
+        )
+    }
+}
+
+/// Tipo de um trecho do código fonte que não forma token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    Whitespace,   // Espaços, tabulações e quebras de linha
+    LineComment,  // `# ...` até o fim da linha (sem a quebra de linha)
+    BlockComment, // `/* ... */`
+    Skipped,      // Caractere descartado por um erro léxico
+}
+
+/// Trecho do código fonte ignorado pela análise sintática, preservado junto aos tokens
+/// para que o código possa ser reconstruído exatamente.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Trivia {
+    pub kind: TriviaKind, // O tipo do trecho
+    pub text: String,     // O texto original
+    pub span: Span,       // A posição do trecho no código fonte
+}
+
+impl std::fmt::Debug for Trivia {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}({:?})", self.kind, self.text)
+    }
+}
+
 /// Struct para tokens usados durante a análise.
 /// Tokens são gerados na análise léxica e repassados para a análise sintática
 #[derive(Debug)]
 pub struct Token {
-    pub token_type: TokenType, // O tipo de token
-    pub lexeme: String,        // O lexema associado (a string literal)
-    pub value: TokenValue,     // O valor associado (se houver)
-    pub terminal: Terminal,    // O símbolo terminal que será usado na análise sintática
+    pub token_type: TokenType,        // O tipo de token
+    pub lexeme: String,               // O lexema associado (a string literal)
+    pub value: TokenValue,            // O valor associado (se houver)
+    pub terminal: Terminal,           // O símbolo terminal que será usado na análise sintática
+    pub span: Span,                   // A posição do token no código fonte
+    pub leading_trivia: Vec<Trivia>,  // Trivia entre o token anterior e este
+    pub trailing_trivia: Vec<Trivia>, // Trivia após o último token da entrada (vazia nos demais)
+}
+
+impl Token {
+    /// Nome do terminal do token na gramática.
+    /// Palavras reservadas fora da LSI-2024-2 usam o próprio lexema como nome.
+    pub fn terminal_name(&self) -> &str {
+        match self.terminal {
+            Terminal::Keyword => &self.lexeme,
+            terminal => terminal.as_str(),
+        }
+    }
 }
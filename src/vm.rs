@@ -0,0 +1,194 @@
+/// Máquina virtual de pilha que executa o bytecode de `bytecode`.
+///
+/// A semântica é a mesma do interpretador (`interpreter`): divisão por zero, estouro aritmético
+/// e recursão acima de `MAX_CALL_DEPTH` chamadas interrompem a execução com um erro.
+///
+/// Os campos de `Module` são públicos, então o módulo pode não ter vindo de
+/// `bytecode::compile`: instruções inválidas, slots e funções inexistentes, chamadas com o
+/// número errado de argumentos e instruções sem operandos suficientes na pilha também
+/// interrompem a execução com um erro.
+use std::fmt;
+use std::io::Write;
+
+use crate::ast::RelOp;
+use crate::bytecode::{decode, Decoded, Module, Opcode};
+use crate::interpreter::MAX_CALL_DEPTH;
+
+/// Erro de execução da máquina virtual, com a posição da instrução que o causou.
+#[derive(Debug, Clone)]
+pub struct VmError {
+    pub message: String,  // Descrição do erro
+    pub function: String, // Função em execução
+    pub offset: usize,    // Offset da instrução na função
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Erro de execução em `{}` (offset {:04x}): {}",
+            self.function, self.offset, self.message
+        )
+    }
+}
+
+/// Quadro de ativação de uma chamada.
+struct Frame {
+    function: usize,  // Índice da função no módulo
+    pc: usize,        // Offset da próxima instrução
+    locals: Vec<i32>, // Slots da função
+}
+
+/// Executa o módulo a partir da função `entry`, escrevendo a saída de `PRINT` em `out`.
+///
+/// # Retorna
+/// - `Ok(i32)`: O valor retornado pela função de entrada.
+/// - `Err(VmError)`: O erro que interrompeu a execução.
+pub fn run(module: &Module, entry: &str, out: &mut dyn Write) -> Result<i32, VmError> {
+    let error = |message: String| VmError {
+        message,
+        function: entry.to_string(),
+        offset: 0,
+    };
+    let Some(function) = module.function_index(entry) else {
+        return Err(error(format!(
+            "a função de entrada `{}` não foi definida",
+            entry
+        )));
+    };
+    if module.functions[function].arity != 0 {
+        return Err(error(format!(
+            "a função de entrada `{}` não pode receber parâmetros",
+            entry
+        )));
+    }
+
+    let mut stack: Vec<i32> = vec![];
+    let mut frames = vec![new_frame(module, function, vec![])];
+
+    loop {
+        let frame = frames.last_mut().unwrap();
+        let (current, offset) = (frame.function, frame.pc);
+        let code = &module.functions[current].code;
+        let error = |message: String| VmError {
+            message,
+            function: module.functions[current].name.clone(),
+            offset,
+        };
+        let underflow = || error("pilha de operandos vazia".to_string());
+        let missing_slot = |slot: u16| error(format!("slot {} inexistente", slot));
+
+        let Some((instr, next)) = decode(code, offset) else {
+            return Err(error("instrução inválida".to_string()));
+        };
+        frame.pc = next;
+
+        match instr {
+            Decoded::Push(value) => stack.push(value),
+            Decoded::Load(slot) => match frame.locals.get(slot as usize) {
+                Some(value) => stack.push(*value),
+                None => return Err(missing_slot(slot)),
+            },
+            Decoded::Store(slot) => {
+                let value = stack.pop().ok_or_else(underflow)?;
+                match frame.locals.get_mut(slot as usize) {
+                    Some(local) => *local = value,
+                    None => return Err(missing_slot(slot)),
+                }
+            }
+            Decoded::Arith(opcode) => {
+                let (b, a) = pop_pair(&mut stack).ok_or_else(underflow)?;
+                if opcode == Opcode::Div && b == 0 {
+                    return Err(error("divisão por zero".to_string()));
+                }
+                let result = match opcode {
+                    Opcode::Add => a.checked_add(b),
+                    Opcode::Sub => a.checked_sub(b),
+                    Opcode::Mul => a.checked_mul(b),
+                    _ => a.checked_div(b),
+                };
+                match result {
+                    Some(value) => stack.push(value),
+                    None => {
+                        return Err(error(format!(
+                            "estouro aritmético em {} {} {}",
+                            opcode.as_str(),
+                            a,
+                            b
+                        )))
+                    }
+                }
+            }
+            Decoded::Cmp(op) => {
+                let (b, a) = pop_pair(&mut stack).ok_or_else(underflow)?;
+                let result = match op {
+                    RelOp::Lt => a < b,
+                    RelOp::Le => a <= b,
+                    RelOp::Gt => a > b,
+                    RelOp::Ge => a >= b,
+                    RelOp::Eq => a == b,
+                    RelOp::Ne => a != b,
+                };
+                stack.push(result as i32);
+            }
+            Decoded::Jump(target) => frame.pc = target as usize,
+            Decoded::JumpIfFalse(target) => {
+                if stack.pop().ok_or_else(underflow)? == 0 {
+                    frame.pc = target as usize;
+                }
+            }
+            Decoded::Call { function, argc } => {
+                let Some(callee) = module.functions.get(function as usize) else {
+                    return Err(error(format!("função #{} inexistente", function)));
+                };
+                if callee.arity != argc as usize {
+                    return Err(error(format!(
+                        "chamada a `{}` com {} argumento(s); a função recebe {}",
+                        callee.name, argc, callee.arity
+                    )));
+                }
+                if frames.len() == MAX_CALL_DEPTH {
+                    return Err(error(format!(
+                        "limite de {} chamadas aninhadas excedido ao chamar `{}`",
+                        MAX_CALL_DEPTH, callee.name
+                    )));
+                }
+                let Some(base) = stack.len().checked_sub(argc as usize) else {
+                    return Err(underflow());
+                };
+                let args = stack.split_off(base);
+                frames.push(new_frame(module, function as usize, args));
+            }
+            Decoded::Ret => {
+                let value = stack.pop().ok_or_else(underflow)?;
+                frames.pop();
+                if frames.is_empty() {
+                    return Ok(value);
+                }
+                stack.push(value);
+            }
+            Decoded::Print => {
+                let value = stack.pop().ok_or_else(underflow)?;
+                writeln!(out, "{}", value)
+                    .map_err(|err| error(format!("falha ao escrever a saída: {}", err)))?;
+            }
+        }
+    }
+}
+
+/// Desempilha os operandos `b` e `a` de uma operação binária, nessa ordem.
+fn pop_pair(stack: &mut Vec<i32>) -> Option<(i32, i32)> {
+    let b = stack.pop()?;
+    let a = stack.pop()?;
+    Some((b, a))
+}
+
+/// Cria o quadro de uma chamada: os argumentos ocupam os primeiros slots e os demais começam com 0.
+fn new_frame(module: &Module, function: usize, mut args: Vec<i32>) -> Frame {
+    args.resize(module.functions[function].slot_names.len(), 0);
+    Frame {
+        function,
+        pc: 0,
+        locals: args,
+    }
+}
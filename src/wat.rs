@@ -0,0 +1,308 @@
+/// Geração de um módulo WebAssembly no formato de texto (WAT).
+///
+/// Cada `def` vira uma função exportada com o mesmo nome, que recebe e retorna `i32`, e cada
+/// variável declarada com `int` vira um local `i32` (locais começam com 0, como as variáveis
+/// da linguagem). Variáveis que ocultam outra de um escopo externo são renomeadas (`$x.1`,
+/// `$x.2`, ...), já que os locais de uma função WebAssembly não têm escopo de bloco. O `print`
+/// chama a função importada `env.print`, que recebe o valor a imprimir.
+///
+/// As operações aritméticas passam por funções auxiliares do módulo, e divisão por zero,
+/// estouro aritmético e recursão acima de `MAX_CALL_DEPTH` chamadas interrompem a execução
+/// com um trap (`unreachable`). Os nomes internos do módulo (`$runtime_*`) contêm `_`, que
+/// não aparece em nomes da LSI-2024-2. Um programa formado por um único comando vira a função
+/// `principal`. O programa deve ter passado pela análise semântica.
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::ast::{literal_value, BinOp, Expr, Ident, Program, RelOp, Stmt};
+use crate::interpreter::{DEFAULT_ENTRY, MAX_CALL_DEPTH};
+
+/// Gera o módulo WAT do programa.
+pub fn generate(program: &Program) -> String {
+    let mut out = String::new();
+    out.push_str("(module\n");
+    out.push_str("  (import \"env\" \"print\" (func $runtime_print (param i32)))\n");
+    writeln!(out, "\n  {}", RUNTIME.trim()).unwrap();
+
+    for function in &program.functions {
+        generate_function(
+            &mut out,
+            &function.name.name,
+            &function.params,
+            &function.body,
+        );
+    }
+    if let Some(statement) = &program.statement {
+        generate_function(
+            &mut out,
+            DEFAULT_ENTRY,
+            &[],
+            std::slice::from_ref(statement),
+        );
+    }
+
+    out.push_str(")\n");
+    out
+}
+
+/// Profundidade de recursão e funções auxiliares, já indentadas para o corpo do módulo.
+const RUNTIME: &str = r#"
+  ;; Número de chamadas em andamento
+  (global $runtime_depth (mut i32) (i32.const 0))
+
+  (func $runtime_check (param $value i64) (result i32)
+    local.get $value
+    i64.const -2147483648
+    i64.lt_s
+    local.get $value
+    i64.const 2147483647
+    i64.gt_s
+    i32.or
+    if
+      unreachable
+    end
+    local.get $value
+    i32.wrap_i64)
+
+  (func $runtime_add (param $a i32) (param $b i32) (result i32)
+    local.get $a
+    i64.extend_i32_s
+    local.get $b
+    i64.extend_i32_s
+    i64.add
+    call $runtime_check)
+
+  (func $runtime_sub (param $a i32) (param $b i32) (result i32)
+    local.get $a
+    i64.extend_i32_s
+    local.get $b
+    i64.extend_i32_s
+    i64.sub
+    call $runtime_check)
+
+  (func $runtime_mul (param $a i32) (param $b i32) (result i32)
+    local.get $a
+    i64.extend_i32_s
+    local.get $b
+    i64.extend_i32_s
+    i64.mul
+    call $runtime_check)
+
+  (func $runtime_div (param $a i32) (param $b i32) (result i32)
+    local.get $b
+    i32.eqz
+    if
+      unreachable
+    end
+    local.get $a
+    i64.extend_i32_s
+    local.get $b
+    i64.extend_i32_s
+    i64.div_s
+    call $runtime_check)
+"#;
+
+fn generate_function(out: &mut String, name: &str, params: &[Ident], body: &[Stmt]) {
+    let mut generator = Generator {
+        body: String::new(),
+        indent: 2,
+        params: vec![],
+        locals: vec![],
+        scopes: vec![HashMap::new()],
+        versions: HashMap::new(),
+    };
+    // Parâmetros e corpo compartilham o mesmo escopo
+    for param in params {
+        let param = generator.declare(&param.name);
+        generator.params.push(param);
+    }
+
+    generator.enter();
+    for stmt in body {
+        generator.stmt(stmt);
+    }
+    if !matches!(body.last(), Some(Stmt::Return(_))) {
+        generator.instr("i32.const 0");
+        generator.leave();
+    }
+
+    write!(out, "\n  (func ${} (export \"{}\")", name, name).unwrap();
+    for param in &generator.params {
+        write!(out, " (param ${} i32)", param).unwrap();
+    }
+    out.push_str(" (result i32)\n");
+    for local in &generator.locals {
+        writeln!(out, "    (local ${} i32)", local).unwrap();
+    }
+    out.push_str(&generator.body);
+    // Fecha a função na última instrução
+    out.pop();
+    out.push_str(")\n");
+}
+
+/// Estado da geração de uma função.
+struct Generator {
+    body: String,                         // Instruções geradas
+    indent: usize,                        // Nível de indentação da próxima instrução
+    params: Vec<String>,                  // Parâmetros (já renomeados)
+    locals: Vec<String>,                  // Locais (já renomeados), na ordem de declaração
+    scopes: Vec<HashMap<String, String>>, // Nome no código fonte -> nome do local, por escopo
+    versions: HashMap<String, usize>,     // Quantas vezes cada nome já foi declarado na função
+}
+
+impl Generator {
+    fn instr(&mut self, instr: &str) {
+        writeln!(self.body, "{}{}", "  ".repeat(self.indent), instr).unwrap();
+    }
+
+    /// Conta a chamada e interrompe a execução se o limite de chamadas for excedido.
+    fn enter(&mut self) {
+        self.instr("global.get $runtime_depth");
+        self.instr("i32.const 1");
+        self.instr("i32.add");
+        self.instr("global.set $runtime_depth");
+        self.instr("global.get $runtime_depth");
+        self.instr(&format!("i32.const {}", MAX_CALL_DEPTH));
+        self.instr("i32.gt_s");
+        self.instr("if");
+        self.instr("  unreachable");
+        self.instr("end");
+    }
+
+    /// Desconta a chamada e retorna o valor no topo da pilha.
+    fn leave(&mut self) {
+        self.instr("global.get $runtime_depth");
+        self.instr("i32.const 1");
+        self.instr("i32.sub");
+        self.instr("global.set $runtime_depth");
+        self.instr("return");
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::VarDecl(names) => {
+                for name in names {
+                    let local = self.declare(&name.name);
+                    self.locals.push(local);
+                }
+            }
+            Stmt::Assign { target, value } => {
+                self.expr(value);
+                let target = self.var(target);
+                self.instr(&format!("local.set ${}", target));
+            }
+            Stmt::Print(expr) => {
+                self.expr(expr);
+                self.instr("call $runtime_print");
+            }
+            Stmt::Return(value) => {
+                match value {
+                    Some(name) => {
+                        let name = self.var(name);
+                        self.instr(&format!("local.get ${}", name));
+                    }
+                    None => self.instr("i32.const 0"),
+                }
+                self.leave();
+            }
+            Stmt::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                self.instr("if");
+                self.scoped(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.instr("else");
+                    self.scoped(else_branch);
+                }
+                self.instr("end");
+            }
+            Stmt::Block(stmts) => {
+                self.scopes.push(HashMap::new());
+                for stmt in stmts {
+                    self.stmt(stmt);
+                }
+                self.scopes.pop();
+            }
+            Stmt::Empty => {}
+        }
+    }
+
+    /// Traduz um ramo de `if` em um escopo próprio, como na análise semântica.
+    fn scoped(&mut self, stmt: &Stmt) {
+        self.indent += 1;
+        self.scopes.push(HashMap::new());
+        self.stmt(stmt);
+        self.scopes.pop();
+        self.indent -= 1;
+    }
+
+    /// Empilha o valor da expressão, avaliando os operandos da esquerda para a direita.
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Num { value, .. } => self.instr(&format!("i32.const {}", literal_value(value))),
+            Expr::Var(name) => {
+                let name = self.var(name);
+                self.instr(&format!("local.get ${}", name));
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                self.expr(lhs);
+                self.expr(rhs);
+                let helper = match op {
+                    BinOp::Add => "add",
+                    BinOp::Sub => "sub",
+                    BinOp::Mul => "mul",
+                    BinOp::Div => "div",
+                };
+                self.instr(&format!("call $runtime_{}", helper));
+            }
+            Expr::Relational { op, lhs, rhs } => {
+                self.expr(lhs);
+                self.expr(rhs);
+                let instr = match op {
+                    RelOp::Lt => "i32.lt_s",
+                    RelOp::Le => "i32.le_s",
+                    RelOp::Gt => "i32.gt_s",
+                    RelOp::Ge => "i32.ge_s",
+                    RelOp::Eq => "i32.eq",
+                    RelOp::Ne => "i32.ne",
+                };
+                self.instr(instr);
+            }
+            Expr::Call(call) => {
+                for arg in &call.args {
+                    let arg = self.var(arg);
+                    self.instr(&format!("local.get ${}", arg));
+                }
+                self.instr(&format!("call ${}", call.name.name));
+            }
+        }
+    }
+
+    /// Declara `name` no escopo atual e retorna o nome do seu local.
+    fn declare(&mut self, name: &str) -> String {
+        let version = self.versions.entry(name.to_string()).or_insert(0);
+        let unique = match *version {
+            0 => name.to_string(),
+            n => format!("{}.{}", name, n),
+        };
+        *version += 1;
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), unique.clone());
+        unique
+    }
+
+    /// Nome do local da variável `name` visível no escopo atual.
+    fn var(&self, name: &Ident) -> String {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.name))
+            .unwrap_or_else(|| panic!("variável `{}` não declarada", name.name))
+            .clone()
+    }
+}
@@ -0,0 +1,361 @@
+/// Geração de código assembly x86-64 (System V, sintaxe AT&T do GNU as).
+///
+/// Cada função do código de três endereços (`tac`) vira uma função nativa `lsi_<nome>`
+/// (o prefixo evita conflitos com funções da biblioteca C, como `printf` ou `main`), e o
+/// código de suporte usa rótulos `.Lruntime.*`, que não conflitam com nomes da LSI-2024-2.
+/// Parâmetros, variáveis locais e temporários ocupam 8 bytes cada na pilha, abaixo de `%rbp`,
+/// e as operações usam registradores de 32 bits. Os seis primeiros argumentos são passados
+/// em registradores e os demais na pilha, como na convenção System V.
+///
+/// O `print` chama `printf` da biblioteca C, e a função `main` gerada chama a função de entrada.
+/// Divisão por zero, estouro aritmético e recursão acima de `MAX_CALL_DEPTH` chamadas
+/// encerram o programa com uma mensagem na saída de erro e código 1, como no interpretador.
+///
+/// O resultado pode ser montado e ligado com o compilador C do sistema:
+/// ```text
+/// cc -o programa programa.s
+/// ```
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::ast::{BinOp, RelOp};
+use crate::interpreter::MAX_CALL_DEPTH;
+use crate::tac::{Function, Instr, Operand, TacProgram};
+
+/// Registradores dos seis primeiros argumentos inteiros (32 bits).
+const ARG_REGISTERS: [&str; 6] = ["%edi", "%esi", "%edx", "%ecx", "%r8d", "%r9d"];
+
+/// Gera o assembly do programa, com uma função `main` que chama a função `entry`.
+///
+/// # Retorna
+/// - `Ok(String)`: O código assembly.
+/// - `Err(String)`: A função de entrada não existe ou recebe parâmetros.
+pub fn generate(program: &TacProgram, entry: &str) -> Result<String, String> {
+    match program
+        .functions
+        .iter()
+        .find(|function| function.name == entry)
+    {
+        None => return Err(format!("a função de entrada `{}` não foi definida", entry)),
+        Some(function) if !function.params.is_empty() => {
+            return Err(format!(
+                "a função de entrada `{}` não pode receber parâmetros",
+                entry
+            ))
+        }
+        Some(_) => {}
+    }
+
+    let mut out = String::new();
+    out.push_str("    .text\n");
+    for function in &program.functions {
+        generate_function(&mut out, function);
+    }
+    generate_runtime(&mut out, entry);
+    Ok(out)
+}
+
+/// Nome do símbolo de uma função da LSI-2024-2.
+fn symbol(name: &str) -> String {
+    format!("lsi_{}", name)
+}
+
+/// Código comum: `main`, o tratamento de erros de execução e os dados.
+fn generate_runtime(out: &mut String, entry: &str) {
+    let code = format!(
+        r#"
+    .globl main
+    .type main, @function
+main:
+    pushq %rbp
+    movq %rsp, %rbp
+    call {entry}
+    xorl %eax, %eax
+    popq %rbp
+    ret
+
+# Imprime a mensagem de erro em %rdi na saída de erro e encerra o programa com código 1
+.Lruntime.error:
+    pushq %rbp
+    movq %rsp, %rbp
+    movq stderr@GOTPCREL(%rip), %rax
+    movq (%rax), %rsi
+    call fputs@PLT
+    movl $1, %edi
+    call exit@PLT
+
+.Lruntime.division_by_zero:
+    leaq .Lruntime.msg_division_by_zero(%rip), %rdi
+    call .Lruntime.error
+
+.Lruntime.overflow:
+    leaq .Lruntime.msg_overflow(%rip), %rdi
+    call .Lruntime.error
+
+.Lruntime.call_depth:
+    leaq .Lruntime.msg_call_depth(%rip), %rdi
+    call .Lruntime.error
+
+    .section .rodata
+.Lruntime.fmt_print:
+    .string "%d\n"
+.Lruntime.msg_division_by_zero:
+    .string "Erro de execução: divisão por zero\n"
+.Lruntime.msg_overflow:
+    .string "Erro de execução: estouro aritmético\n"
+.Lruntime.msg_call_depth:
+    .string "Erro de execução: limite de {depth} chamadas aninhadas excedido\n"
+
+    .bss
+    .align 4
+.Lruntime.depth:
+    .zero 4
+
+    .section .note.GNU-stack,"",@progbits
+"#,
+        entry = symbol(entry),
+        depth = MAX_CALL_DEPTH
+    );
+    out.push_str(&code);
+}
+
+fn generate_function(out: &mut String, function: &Function) {
+    // Parâmetros, locais e temporários, nessa ordem; temporários são indexados à parte,
+    // já que uma variável pode se chamar `t1`
+    let variables: HashMap<&str, usize> = function
+        .params
+        .iter()
+        .chain(&function.locals)
+        .enumerate()
+        .map(|(slot, name)| (name.as_str(), slot))
+        .collect();
+    let slots = function.params.len() + function.locals.len() + function.temps;
+
+    let mut emitter = Emitter {
+        out,
+        function: &function.name,
+        variables,
+        temps: function.params.len() + function.locals.len(),
+        pending: vec![],
+        checks: 0,
+    };
+
+    let name = symbol(&function.name);
+    emitter.line(&format!("\n    .globl {}", name));
+    emitter.line(&format!("    .type {}, @function", name));
+    emitter.line(&format!("{}:", name));
+    emitter.instr("pushq %rbp");
+    emitter.instr("movq %rsp, %rbp");
+    // Mantém %rsp alinhado em 16 bytes para as chamadas
+    let frame = (slots * 8).div_ceil(16) * 16;
+    if frame > 0 {
+        emitter.instr(&format!("subq ${}, %rsp", frame));
+    }
+
+    emitter.instr("movl .Lruntime.depth(%rip), %eax");
+    emitter.instr("incl %eax");
+    emitter.instr(&format!("cmpl ${}, %eax", MAX_CALL_DEPTH));
+    emitter.instr("jg .Lruntime.call_depth");
+    emitter.instr("movl %eax, .Lruntime.depth(%rip)");
+
+    for (index, param) in function.params.iter().enumerate() {
+        let slot = emitter.slot(&Operand::Var(param.clone()));
+        match ARG_REGISTERS.get(index) {
+            Some(register) => emitter.instr(&format!("movl {}, {}", register, slot)),
+            None => {
+                // Argumentos excedentes estão acima do endereço de retorno
+                let offset = 16 + 8 * (index - ARG_REGISTERS.len());
+                emitter.instr(&format!("movl {}(%rbp), %eax", offset));
+                emitter.instr(&format!("movl %eax, {}", slot));
+            }
+        }
+    }
+
+    for instr in &function.body {
+        emitter.emit(instr);
+    }
+
+    emitter.line(&format!("{}:", emitter.label("ret")));
+    emitter.instr("decl .Lruntime.depth(%rip)");
+    emitter.instr("leave");
+    emitter.instr("ret");
+}
+
+/// Estado da geração de uma função.
+struct Emitter<'a> {
+    out: &'a mut String,
+    function: &'a str,
+    variables: HashMap<&'a str, usize>, // Variável -> slot
+    temps: usize,                       // Slot do primeiro temporário
+    pending: Vec<Operand>,              // Argumentos (`param`) da próxima chamada
+    checks: usize,                      // Rótulos gerados para as verificações de divisão
+}
+
+impl Emitter<'_> {
+    fn line(&mut self, line: &str) {
+        writeln!(self.out, "{}", line).unwrap();
+    }
+
+    fn instr(&mut self, instr: &str) {
+        writeln!(self.out, "    {}", instr).unwrap();
+    }
+
+    /// Rótulo local da função.
+    fn label(&self, name: &str) -> String {
+        format!(".L{}_{}", self.function, name)
+    }
+
+    /// Endereço na pilha de uma variável ou temporário.
+    fn slot(&self, operand: &Operand) -> String {
+        let slot = match operand {
+            Operand::Var(name) => self.variables[name.as_str()],
+            Operand::Temp(index) => self.temps + index - 1,
+            Operand::Const(_) => unreachable!("constantes não ocupam slots"),
+        };
+        format!("-{}(%rbp)", 8 * (slot + 1))
+    }
+
+    /// Operando de uma instrução: um imediato ou um endereço na pilha.
+    fn operand(&self, operand: &Operand) -> String {
+        match operand {
+            Operand::Const(value) => format!("${}", value),
+            _ => self.slot(operand),
+        }
+    }
+
+    fn load(&mut self, operand: &Operand, register: &str) {
+        let source = self.operand(operand);
+        self.instr(&format!("movl {}, {}", source, register));
+    }
+
+    fn emit(&mut self, instr: &Instr) {
+        match instr {
+            Instr::Copy { dest, src } => {
+                self.load(src, "%eax");
+                let dest = self.slot(dest);
+                self.instr(&format!("movl %eax, {}", dest));
+            }
+            Instr::Binary { dest, op, lhs, rhs } => {
+                self.load(lhs, "%eax");
+                self.load(rhs, "%ecx");
+                match op {
+                    BinOp::Add => self.instr("addl %ecx, %eax"),
+                    BinOp::Sub => self.instr("subl %ecx, %eax"),
+                    BinOp::Mul => self.instr("imull %ecx, %eax"),
+                    BinOp::Div => {
+                        self.instr("testl %ecx, %ecx");
+                        self.instr("je .Lruntime.division_by_zero");
+                        // `idivl` também falha em INT_MIN / -1, que não cabe em 32 bits
+                        self.checks += 1;
+                        let ok = self.label(&format!("div{}", self.checks));
+                        self.instr("cmpl $-1, %ecx");
+                        self.instr(&format!("jne {}", ok));
+                        self.instr("cmpl $-2147483648, %eax");
+                        self.instr("je .Lruntime.overflow");
+                        self.line(&format!("{}:", ok));
+                        self.instr("cltd");
+                        self.instr("idivl %ecx");
+                    }
+                }
+                if *op != BinOp::Div {
+                    self.instr("jo .Lruntime.overflow");
+                }
+                let dest = self.slot(dest);
+                self.instr(&format!("movl %eax, {}", dest));
+            }
+            Instr::Relational { dest, op, lhs, rhs } => {
+                self.load(lhs, "%eax");
+                self.load(rhs, "%ecx");
+                self.instr("cmpl %ecx, %eax");
+                self.instr(&format!("set{} %al", condition(*op)));
+                self.instr("movzbl %al, %eax");
+                let dest = self.slot(dest);
+                self.instr(&format!("movl %eax, {}", dest));
+            }
+            Instr::IfFalse {
+                op,
+                lhs,
+                rhs,
+                target,
+            } => {
+                self.load(lhs, "%eax");
+                self.load(rhs, "%ecx");
+                self.instr("cmpl %ecx, %eax");
+                let target = self.label(&target.to_string());
+                self.instr(&format!("j{} {}", condition(negate(*op)), target));
+            }
+            Instr::Goto(target) => {
+                let target = self.label(&target.to_string());
+                self.instr(&format!("jmp {}", target));
+            }
+            Instr::Label(label) => {
+                let label = self.label(&label.to_string());
+                self.line(&format!("{}:", label));
+            }
+            Instr::Param(arg) => self.pending.push(arg.clone()),
+            Instr::Call { dest, function, .. } => {
+                let args = std::mem::take(&mut self.pending);
+                let stack_args = args.len().saturating_sub(ARG_REGISTERS.len());
+
+                // Argumentos excedentes vão para a pilha, do último para o primeiro,
+                // mantendo %rsp alinhado em 16 bytes no momento da chamada
+                if stack_args % 2 == 1 {
+                    self.instr("subq $8, %rsp");
+                }
+                for arg in args[ARG_REGISTERS.len().min(args.len())..].iter().rev() {
+                    self.load(arg, "%eax");
+                    self.instr("pushq %rax");
+                }
+                for (arg, register) in args.iter().zip(ARG_REGISTERS) {
+                    self.load(arg, register);
+                }
+
+                self.instr(&format!("call {}", symbol(function)));
+                if stack_args > 0 {
+                    let size = (stack_args + stack_args % 2) * 8;
+                    self.instr(&format!("addq ${}, %rsp", size));
+                }
+                let dest = self.slot(dest);
+                self.instr(&format!("movl %eax, {}", dest));
+            }
+            Instr::Return(value) => {
+                match value {
+                    Some(value) => self.load(value, "%eax"),
+                    None => self.instr("xorl %eax, %eax"),
+                }
+                let ret = self.label("ret");
+                self.instr(&format!("jmp {}", ret));
+            }
+            Instr::Print(value) => {
+                self.load(value, "%esi");
+                self.instr("leaq .Lruntime.fmt_print(%rip), %rdi");
+                self.instr("xorl %eax, %eax");
+                self.instr("call printf@PLT");
+            }
+        }
+    }
+}
+
+/// Sufixo da condição de `set`/`j` para a comparação com sinal `a op b`.
+fn condition(op: RelOp) -> &'static str {
+    match op {
+        RelOp::Lt => "l",
+        RelOp::Le => "le",
+        RelOp::Gt => "g",
+        RelOp::Ge => "ge",
+        RelOp::Eq => "e",
+        RelOp::Ne => "ne",
+    }
+}
+
+/// Operador com o resultado oposto, usado nos desvios de `ifFalse`.
+fn negate(op: RelOp) -> RelOp {
+    match op {
+        RelOp::Lt => RelOp::Ge,
+        RelOp::Le => RelOp::Gt,
+        RelOp::Gt => RelOp::Le,
+        RelOp::Ge => RelOp::Lt,
+        RelOp::Eq => RelOp::Ne,
+        RelOp::Ne => RelOp::Eq,
+    }
+}
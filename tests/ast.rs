@@ -0,0 +1,136 @@
+//! Testes da construção da AST: cada construção da linguagem vira o nó esperado, e as listas
+//! recursivas da gramática viram vetores na ordem do código fonte.
+mod common;
+
+use common::program;
+use parser::ast::{Expr, Ident, Program, Stmt};
+
+/// Escreve a AST de volta como código, com parênteses em toda expressão binária, para
+/// comparar a forma da árvore.
+fn show(program: &Program) -> String {
+    let mut out: Vec<String> = program
+        .functions
+        .iter()
+        .map(|function| {
+            format!(
+                "def {}({}) {}",
+                function.name.name,
+                names(&function.params),
+                block(&function.body)
+            )
+        })
+        .collect();
+    out.extend(program.statement.iter().map(stmt));
+    out.join("\n")
+}
+
+fn names(idents: &[Ident]) -> String {
+    let names: Vec<&str> = idents.iter().map(|ident| ident.name.as_str()).collect();
+    names.join(", ")
+}
+
+fn block(stmts: &[Stmt]) -> String {
+    let stmts: Vec<String> = stmts.iter().map(stmt).collect();
+    format!("{{ {} }}", stmts.join(" "))
+}
+
+fn stmt(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::VarDecl(vars) => format!("int {};", names(vars)),
+        Stmt::Assign { target, value } => format!("{} := {};", target.name, expr(value)),
+        Stmt::Print(value) => format!("print {};", expr(value)),
+        Stmt::Return(None) => "return;".to_string(),
+        Stmt::Return(Some(name)) => format!("return {};", name.name),
+        Stmt::If {
+            cond,
+            then_branch,
+            else_branch,
+        } => match else_branch {
+            None => format!("if ({}) {}", expr(cond), self::stmt(then_branch)),
+            Some(else_branch) => format!(
+                "if ({}) {} else {}",
+                expr(cond),
+                self::stmt(then_branch),
+                self::stmt(else_branch)
+            ),
+        },
+        Stmt::Block(stmts) => block(stmts),
+        Stmt::Empty => ";".to_string(),
+    }
+}
+
+fn expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Num { value, .. } => value.to_string(),
+        Expr::Var(name) => name.name.clone(),
+        Expr::Binary { op, lhs, rhs } => {
+            format!("({} {} {})", self::expr(lhs), op.as_str(), self::expr(rhs))
+        }
+        Expr::Relational { op, lhs, rhs } => {
+            format!("({} {} {})", self::expr(lhs), op.as_str(), self::expr(rhs))
+        }
+        Expr::Call(call) => format!("{}({})", call.name.name, names(&call.args)),
+    }
+}
+
+#[test]
+fn functions_statements_and_nested_blocks() {
+    let program = program(
+        "def f(int a, int b, int c) {
+            int r, s;
+            if (a < b) { r := g(a, b, c); } else { { int d; d := b * 2 + c; print d; } }
+            if (r) ; else print 0;
+            if (a - b * c == 7) return;
+            { { } }
+            return r;
+        }
+        def g(int x, int y, int z) { return x; }
+        def principal() { int x; x := f(x, x, x); return; }",
+    );
+    assert_eq!(
+        show(&program),
+        "\
+def f(a, b, c) { int r, s; if ((a < b)) { r := g(a, b, c); } else { { int d; d := ((b * 2) + c); print d; } } if (r) ; else print 0; if (((a - (b * c)) == 7)) return; { {  } } return r; }
+def g(x, y, z) { return x; }
+def principal() { int x; x := f(x, x, x); return; }"
+    );
+    assert!(program.statement.is_none());
+
+    // Os nós guardam a posição dos identificadores
+    let Stmt::If { then_branch, .. } = &program.functions[0].body[1] else {
+        panic!("esperado um `if`");
+    };
+    let Stmt::Block(stmts) = then_branch.as_ref() else {
+        panic!("esperado um bloco");
+    };
+    let Stmt::Assign {
+        target,
+        value: Expr::Call(call),
+    } = &stmts[0]
+    else {
+        panic!("esperada uma atribuição de chamada");
+    };
+    assert_eq!((target.span.line, target.span.column), (3, 26));
+    assert_eq!((call.name.span.line, call.name.span.column), (3, 31));
+    assert_eq!((call.args[2].span.line, call.args[2].span.column), (3, 39));
+}
+
+#[test]
+fn operators_follow_precedence_and_associativity() {
+    let program = program("print 1 - 2 - 3 * 4 / (5 + 6) <= 7;");
+    assert_eq!(
+        show(&program),
+        "print (((1 - 2) - ((3 * 4) / (5 + 6))) <= 7);"
+    );
+}
+
+#[test]
+fn a_single_statement_is_the_program() {
+    let statement = program("{ int x; x := 1; if (x <> 0) { print x; } }");
+    assert!(statement.functions.is_empty());
+    assert_eq!(
+        show(&statement),
+        "{ int x; x := 1; if ((x <> 0)) { print x; } }"
+    );
+    assert_eq!(show(&program("")), "");
+}
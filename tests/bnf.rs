@@ -0,0 +1,165 @@
+//! Testes do leitor de gramáticas BNF: cada erro de formato e de validação é relatado
+//! com a linha em que ocorre.
+use parser::bnf::{grammar_keywords, parse_grammar};
+use parser::ll1_table::LSI_GRAMMAR;
+use parser::Diagnostic;
+
+/// Mensagens e linhas dos erros da gramática, que deve ser inválida.
+fn errors(source: &str) -> Vec<(String, usize)> {
+    parse_grammar(source)
+        .expect_err("a gramática deveria ser inválida")
+        .into_iter()
+        .filter(Diagnostic::is_error)
+        .map(|diagnostic| (diagnostic.message, diagnostic.span.line))
+        .collect()
+}
+
+/// Mensagem e linha do único erro da gramática.
+fn error(source: &str) -> (String, usize) {
+    let errors = errors(source);
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    errors.into_iter().next().unwrap()
+}
+
+#[test]
+fn valid_grammars_are_loaded() {
+    let (grammar, warnings) = parse_grammar(LSI_GRAMMAR).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    assert_eq!(grammar.start, "MAIN");
+    assert_eq!(grammar.lookaheads.len(), 1);
+    assert_eq!(grammar.preferences.len(), 3);
+
+    let source = "# comentário\n%start B\nA -> x\nB -> enquanto A | y # fim\n   | ε\n";
+    let (grammar, warnings) = parse_grammar(source).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    assert_eq!(grammar.start, "B");
+    let alternatives: Vec<String> = grammar
+        .productions_of("B")
+        .map(|(_, production)| production.to_string())
+        .collect();
+    assert_eq!(alternatives, ["B -> enquanto A", "B -> y", "B -> ε"]);
+    assert_eq!(
+        grammar_keywords(&grammar),
+        ["enquanto", "x", "y"].map(String::from).into()
+    );
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+    assert_eq!(
+        error("S -> a\n%ignore S"),
+        (
+            "diretiva desconhecida ou mal formada: `%ignore S`".to_string(),
+            2
+        )
+    );
+    assert_eq!(
+        error("S -> a\n%prefer S a a"),
+        (
+            "diretiva desconhecida ou mal formada: `%prefer S a a`".to_string(),
+            2
+        )
+    );
+    assert_eq!(
+        error("S -> a\n%lookahead S -> a").0,
+        "diretiva `%lookahead` mal formada; use `%lookahead A t1 t2 -> alternativa`"
+    );
+    assert_eq!(
+        error("| a\nS -> a"),
+        ("alternativa `|` sem uma regra anterior".to_string(), 1)
+    );
+    assert_eq!(
+        error("S -> a\nA B -> b").0,
+        "o lado esquerdo de uma regra deve ser um único não terminal"
+    );
+    assert_eq!(
+        error("S -> a\nS b"),
+        ("regra sem `->`: `S b`".to_string(), 2)
+    );
+    assert_eq!(error("S -> a | | b").0, "alternativa vazia em `S`; use `ε`");
+    assert_eq!(
+        error("# apenas comentários\n").0,
+        "a gramática não possui nenhuma regra"
+    );
+}
+
+#[test]
+fn directives_must_refer_to_declared_productions() {
+    assert_eq!(
+        error("S -> a | b\n%prefer S a -> c"),
+        (
+            "a diretiva se refere à produção `S -> c`, que não foi declarada".to_string(),
+            2
+        )
+    );
+    assert_eq!(
+        error("S -> a | b\n%lookahead T a b -> a").0,
+        "a diretiva se refere à produção `T -> a`, que não foi declarada"
+    );
+}
+
+#[test]
+fn undefined_and_unusable_symbols_are_rejected() {
+    assert_eq!(
+        error("S -> a EXPR"),
+        (
+            "o não terminal `EXPR` é usado mas não possui produções".to_string(),
+            1
+        )
+    );
+    assert_eq!(
+        error("S -> a\n  | @").0,
+        "o terminal `@` não é reconhecido pelo analisador léxico"
+    );
+    assert_eq!(
+        error("%start T\nS -> a").0,
+        "o símbolo inicial `T` não possui produções"
+    );
+    // `S` depende de `B`, que nunca termina
+    assert_eq!(
+        errors("S -> a B\nB -> b B"),
+        ["S", "B"]
+            .into_iter()
+            .zip([1, 2])
+            .map(|(nonterminal, line)| (
+                format!(
+                    "o não terminal `{}` não deriva nenhuma cadeia de terminais",
+                    nonterminal
+                ),
+                line
+            ))
+            .collect::<Vec<_>>()
+    );
+    let recursive: Vec<String> = errors("S -> A a\nA -> S b | c")
+        .into_iter()
+        .map(|(message, _)| message)
+        .collect();
+    assert_eq!(
+        recursive,
+        ["S", "A"].map(|nonterminal| format!(
+            "o não terminal `{}` possui recursão à esquerda e não pode ser analisado por um parser preditivo",
+            nonterminal
+        ))
+    );
+}
+
+#[test]
+fn unreachable_symbols_and_unresolved_conflicts_are_warnings() {
+    let (_, warnings) = parse_grammar("S -> a T | a\nT -> b\nU -> c").unwrap();
+    let messages: Vec<&str> = warnings
+        .iter()
+        .map(|warning| warning.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "o não terminal `U` não é alcançável a partir de `S`",
+            "Conflito FIRST/FIRST em M[S, a] (resolvido pela ordem de declaração):     S -> a T  (padrão)     S -> a (use `%prefer` ou `%lookahead` para declarar a resolução)",
+        ]
+    );
+    assert!(warnings.iter().all(|warning| !warning.is_error()));
+
+    // Com a resolução declarada, o conflito deixa de ser um aviso
+    let (_, warnings) = parse_grammar("S -> a T | a\nT -> b\n%prefer S a -> a T").unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+}
@@ -0,0 +1,406 @@
+//! Testes do bytecode e da máquina virtual: o texto do disassembler, a decodificação das
+//! instruções, os limites do formato e a equivalência entre a VM e o interpretador.
+mod common;
+
+use parser::ast::{BinOp, RelOp};
+use parser::bytecode::{self, decode, CodeFunction, Decoded, Module, Opcode};
+use parser::tac::{Function, Instr, Operand, TacProgram};
+use parser::{interpreter, tac, vm};
+
+use common::program;
+
+fn compile(source: &str) -> Module {
+    bytecode::compile(&tac::lower(&program(source))).unwrap()
+}
+
+/// Função de TAC sem parâmetros com o corpo `body`.
+fn tac_function(name: &str, locals: usize, temps: usize, body: Vec<Instr>) -> Function {
+    Function {
+        name: name.to_string(),
+        params: vec![],
+        locals: (0..locals).map(|index| format!("v{}", index)).collect(),
+        temps,
+        labels: 0,
+        body,
+    }
+}
+
+#[test]
+fn disassembly_lists_every_instruction() {
+    let module = compile(
+        "def dobro(int n) {
+            int r;
+            r := n * 2;
+            return r;
+        }
+        def principal() {
+            int x;
+            x := dobro(x);
+            if (x < 10) print x; else print 0 - x;
+            return;
+        }",
+    );
+    assert_eq!(
+        bytecode::disassemble(&module),
+        "\
+função #0 dobro (parâmetros: 1, slots: 2, 24 bytes):
+  0000  PUSH 0
+  0005  STORE 1       ; r
+  0008  LOAD 0        ; n
+  000b  PUSH 2
+  0010  MUL
+  0011  STORE 1       ; r
+  0014  LOAD 1        ; r
+  0017  RET
+
+função #1 principal (parâmetros: 0, slots: 2, 64 bytes):
+  0000  PUSH 0
+  0005  STORE 0       ; x
+  0008  LOAD 0        ; x
+  000b  CALL 0        ; dobro, 1 argumento(s)
+  000f  STORE 0       ; x
+  0012  LOAD 0        ; x
+  0015  PUSH 10
+  001a  CMP <
+  001c  JUMP_IF_FALSE 002a
+  0021  LOAD 0        ; x
+  0024  PRINT
+  0025  JUMP 003a
+  002a  PUSH 0
+  002f  LOAD 0        ; x
+  0032  SUB
+  0033  STORE 1       ; t1
+  0036  LOAD 1        ; t1
+  0039  PRINT
+  003a  PUSH 0
+  003f  RET
+"
+    );
+
+    // Temporários têm slots próprios, mesmo quando uma variável se chama `t1`
+    let module = compile("def principal() { int t1; print t1 + 1 - 2; }");
+    assert!(bytecode::disassemble(&module).contains(
+        "\
+  0008  LOAD 0        ; t1
+  000b  PUSH 1
+  0010  ADD
+  0011  STORE 1       ; t1
+  0014  LOAD 1        ; t1"
+    ));
+}
+
+#[test]
+fn instructions_are_decoded_with_their_operands() {
+    let mut code = vec![Opcode::Push as u8];
+    code.extend((-5i32).to_le_bytes());
+    code.extend([Opcode::Load as u8, 0x34, 0x12]);
+    code.extend([Opcode::Store as u8, 0xff, 0xff]);
+    code.extend([Opcode::Div as u8, Opcode::Cmp as u8, 5]);
+    code.push(Opcode::Jump as u8);
+    code.extend(0x0102_0304u32.to_le_bytes());
+    code.push(Opcode::JumpIfFalse as u8);
+    code.extend(7u32.to_le_bytes());
+    code.extend([Opcode::Call as u8, 0x02, 0x01, 255]);
+    code.extend([Opcode::Print as u8, Opcode::Ret as u8]);
+
+    let mut decoded = vec![];
+    let mut pc = 0;
+    while pc < code.len() {
+        let (instr, next) = decode(&code, pc).unwrap();
+        decoded.push((pc, instr));
+        pc = next;
+    }
+    assert_eq!(pc, code.len());
+    assert_eq!(
+        decoded,
+        [
+            (0, Decoded::Push(-5)),
+            (5, Decoded::Load(0x1234)),
+            (8, Decoded::Store(u16::MAX)),
+            (11, Decoded::Arith(Opcode::Div)),
+            (12, Decoded::Cmp(RelOp::Ne)),
+            (14, Decoded::Jump(0x0102_0304)),
+            (19, Decoded::JumpIfFalse(7)),
+            (
+                24,
+                Decoded::Call {
+                    function: 0x0102,
+                    argc: 255
+                }
+            ),
+            (28, Decoded::Print),
+            (29, Decoded::Ret),
+        ]
+    );
+
+    // Opcodes desconhecidos, operadores inválidos e operandos incompletos
+    assert_eq!(decode(&[0xee], 0), None);
+    assert_eq!(decode(&[Opcode::Cmp as u8, 6], 0), None);
+    assert_eq!(decode(&[Opcode::Push as u8, 1, 2, 3], 0), None);
+    assert_eq!(decode(&[Opcode::Call as u8, 0, 0], 0), None);
+    assert_eq!(decode(&code, code.len()), None);
+}
+
+#[test]
+fn compiled_code_decodes_back_to_whole_instructions() {
+    let module = compile(
+        "def f(int a, int b) { int r; if (a >= b) r := a / b; else r := a * b; return r; }
+        def principal() { int x, y; x := 7; y := 2; x := f(x, y); print x == 3; return; }",
+    );
+    for function in &module.functions {
+        let mut starts = vec![];
+        let mut targets = vec![];
+        let mut pc = 0;
+        while pc < function.code.len() {
+            let (instr, next) = decode(&function.code, pc).expect("instrução válida");
+            starts.push(pc);
+            match instr {
+                Decoded::Jump(target) | Decoded::JumpIfFalse(target) => {
+                    targets.push(target as usize)
+                }
+                Decoded::Load(slot) | Decoded::Store(slot) => {
+                    assert!((slot as usize) < function.slot_names.len())
+                }
+                Decoded::Call { function, argc } => {
+                    assert_eq!(module.functions[function as usize].arity, argc as usize)
+                }
+                _ => {}
+            }
+            pc = next;
+        }
+        assert_eq!(pc, function.code.len());
+        assert!(
+            targets.iter().all(|target| starts.contains(target)),
+            "desvio para o meio de uma instrução em `{}`",
+            function.name
+        );
+    }
+}
+
+#[test]
+fn format_limits_are_rejected() {
+    let call = |argc: usize| {
+        let mut body = vec![Instr::Param(Operand::Const(1)); argc];
+        body.push(Instr::Call {
+            dest: Operand::Temp(1),
+            function: "principal".to_string(),
+            argc,
+        });
+        body.push(Instr::Return(None));
+        TacProgram {
+            functions: vec![tac_function("principal", 0, 1, body)],
+        }
+    };
+    assert!(bytecode::compile(&call(255)).is_ok());
+    assert_eq!(
+        bytecode::compile(&call(256)).unwrap_err(),
+        "a chamada a `principal` em `principal` tem 256 argumentos; o bytecode aceita no máximo 255"
+    );
+
+    let slots = |locals: usize, temps: usize| {
+        let body = vec![
+            Instr::Binary {
+                dest: Operand::Temp(temps),
+                op: BinOp::Add,
+                lhs: Operand::Var(format!("v{}", locals - 1)),
+                rhs: Operand::Const(1),
+            },
+            Instr::Return(Some(Operand::Temp(temps))),
+        ];
+        TacProgram {
+            functions: vec![tac_function("grande", locals, temps, body)],
+        }
+    };
+    let module = bytecode::compile(&slots(65535, 1)).unwrap();
+    let (instr, _) = decode(&module.functions[0].code, 0).unwrap();
+    assert_eq!(instr, Decoded::Load(65534));
+    assert!(bytecode::disassemble(&module).contains("STORE 65535   ; t1"));
+    assert_eq!(
+        bytecode::compile(&slots(65536, 1)).unwrap_err(),
+        "a função `grande` tem 65537 variáveis e temporários; o bytecode aceita no máximo 65536"
+    );
+}
+
+#[test]
+fn calls_to_undefined_functions_are_rejected() {
+    let body = vec![
+        Instr::Call {
+            dest: Operand::Temp(1),
+            function: "nada".to_string(),
+            argc: 0,
+        },
+        Instr::Return(None),
+    ];
+    let program = TacProgram {
+        functions: vec![tac_function("principal", 0, 1, body)],
+    };
+    assert_eq!(
+        bytecode::compile(&program).unwrap_err(),
+        "a função `principal` chama `nada`, que não foi definida"
+    );
+}
+
+/// Módulo com a função `principal`, sem parâmetros, e as demais funções `(nome, aridade)`.
+fn module(slots: usize, code: Vec<u8>, others: &[(&str, usize)]) -> Module {
+    let mut functions = vec![CodeFunction {
+        name: "principal".to_string(),
+        arity: 0,
+        slot_names: (0..slots).map(|index| format!("v{}", index)).collect(),
+        code,
+    }];
+    for (name, arity) in others {
+        functions.push(CodeFunction {
+            name: name.to_string(),
+            arity: *arity,
+            slot_names: (0..*arity).map(|index| format!("p{}", index)).collect(),
+            code: vec![Opcode::Push as u8, 0, 0, 0, 0, Opcode::Ret as u8],
+        });
+    }
+    Module { functions }
+}
+
+/// Mensagem do erro da máquina virtual ao executar `module`.
+fn vm_error(module: &Module) -> String {
+    let mut output = vec![];
+    vm::run(module, "principal", &mut output)
+        .expect_err("a execução deveria falhar")
+        .to_string()
+}
+
+#[test]
+fn malformed_modules_are_errors_not_panics() {
+    use Opcode::*;
+
+    // Operandos ausentes na pilha
+    for code in [
+        vec![Add as u8],
+        vec![Push as u8, 1, 0, 0, 0, Cmp as u8, 0],
+        vec![Store as u8, 0, 0],
+        vec![JumpIfFalse as u8, 0, 0, 0, 0],
+        vec![Print as u8],
+        vec![Ret as u8],
+    ] {
+        assert!(
+            vm_error(&module(1, code.clone(), &[])).ends_with("pilha de operandos vazia"),
+            "{:?}",
+            code
+        );
+    }
+    assert_eq!(
+        vm_error(&module(0, vec![Call as u8, 1, 0, 2], &[("f", 2)])),
+        "Erro de execução em `principal` (offset 0000): pilha de operandos vazia"
+    );
+
+    // Slots e funções inexistentes
+    assert!(vm_error(&module(1, vec![Load as u8, 1, 0], &[])).ends_with("slot 1 inexistente"));
+    let store = vec![Push as u8, 1, 0, 0, 0, Store as u8, 9, 0];
+    assert_eq!(
+        vm_error(&module(1, store, &[])),
+        "Erro de execução em `principal` (offset 0005): slot 9 inexistente"
+    );
+    assert!(vm_error(&module(0, vec![Call as u8, 7, 0, 0], &[])).ends_with("função #7 inexistente"));
+    let call = vec![Push as u8, 1, 0, 0, 0, Call as u8, 1, 0, 1];
+    assert!(vm_error(&module(0, call, &[("f", 2)]))
+        .ends_with("chamada a `f` com 1 argumento(s); a função recebe 2"));
+
+    // Código truncado, sem `RET` no fim ou com desvio para fora da função
+    for code in [
+        vec![Push as u8, 1, 0],
+        vec![Push as u8, 1, 0, 0, 0],
+        vec![Jump as u8, 0xff, 0, 0, 0],
+        vec![],
+    ] {
+        assert!(
+            vm_error(&module(0, code.clone(), &[])).ends_with("instrução inválida"),
+            "{:?}",
+            code
+        );
+    }
+
+    // O disassembler lista o que consegue ler
+    let module = module(
+        1,
+        vec![
+            Load as u8,
+            3,
+            0,
+            Call as u8,
+            5,
+            0,
+            0,
+            Store as u8,
+            0,
+            0,
+            Push as u8,
+            1,
+        ],
+        &[],
+    );
+    assert_eq!(
+        bytecode::disassemble(&module),
+        "\
+função #0 principal (parâmetros: 0, slots: 1, 12 bytes):
+  0000  LOAD 3        ; ???
+  0003  CALL 5        ; ???, 0 argumento(s)
+  0007  STORE 0       ; v0
+  000a  ??? 0x01
+"
+    );
+}
+
+/// Executa o programa no interpretador e na máquina virtual e compara a saída, o valor
+/// retornado e o sucesso da execução.
+fn assert_same_behaviour(source: &str) {
+    let program = program(source);
+    let mut expected = vec![];
+    let interpreted =
+        interpreter::on_large_stack(|| interpreter::run(&program, "principal", &mut expected));
+
+    let module = bytecode::compile(&tac::lower(&program)).unwrap();
+    let mut output = vec![];
+    let executed = vm::run(&module, "principal", &mut output);
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        String::from_utf8(expected).unwrap()
+    );
+    assert_eq!(executed.ok(), interpreted.ok());
+}
+
+#[test]
+fn the_vm_behaves_like_the_interpreter() {
+    assert_same_behaviour(
+        "def fat(int n) {
+            int r, m, k;
+            if (n <= 1) { r := 1; return r; }
+            m := n - 1;
+            k := fat(m);
+            r := n * k;
+            return r;
+        }
+        def principal() {
+            int x, y;
+            x := 10;
+            y := fat(x);
+            print y;
+            if (y <> 3628800) print 0; else { int x; x := 7; print x; }
+            print x;
+            if (x - 10) print 1; else print 2;
+            print (7 - 20) / 2;
+            print x >= 10;
+            return y;
+        }",
+    );
+    assert_same_behaviour("{ int x; x := 2 * 21; print x; }");
+    assert_same_behaviour("def principal() { int z; print 1; print 1 / z; print 2; return; }");
+    assert_same_behaviour(
+        "def principal() { int x; x := 2147483647; print x; x := x + 1; print x; return; }",
+    );
+    assert_same_behaviour(
+        "def principal() { int x, y; x := 0 - 2147483647 - 1; y := 0 - 1; print x / y; return; }",
+    );
+    assert_same_behaviour(
+        "def conta(int n) { int m, r; print n; m := n + 1; r := conta(m); return r; }
+        def principal() { int x; x := conta(x); return; }",
+    );
+}
@@ -0,0 +1,113 @@
+//! Testes do tradutor para C: o código gerado é compilado com o `cc` do sistema, e a saída
+//! do executável é comparada com a do interpretador.
+mod common;
+
+use common::{program, Native};
+use parser::c;
+
+const C: Native = Native {
+    name: "c",
+    extension: "c",
+    flags: &["-std=c99", "-pedantic-errors"],
+    generate: |program| c::generate(program, "principal").unwrap(),
+};
+
+/// O comando de compilação do README, sem `-std`: o `cc` usa um modo `gnu*`, que define
+/// macros como `linux` e `unix`.
+const GNU_C: Native = Native {
+    name: "gnu-c",
+    extension: "c",
+    flags: &[],
+    generate: |program| c::generate(program, "principal").unwrap(),
+};
+
+/// Compara a saída do executável com a do interpretador e retorna a saída de erro.
+fn assert_same_behaviour(name: &str, source: &str) -> String {
+    common::assert_same_behaviour(&C, name, source)
+}
+
+#[test]
+fn functions_statements_and_scopes() {
+    let source = "def fat(int n) {
+            int r, m, k;
+            if (n <= 1) { r := 1; return r; }
+            m := n - 1;
+            k := fat(m);
+            r := n * k;
+            return r;
+        }
+        def principal() {
+            int x, y;
+            x := 10;
+            y := fat(x);
+            print y;
+            if (y <> 3628800) print 0; else { int x; x := 7; print x; }
+            print x;
+            if (x - 10) print 1; else print 2;
+            print (7 - 20) / 2;
+            print x >= 10;
+            return;
+        }";
+    let code = c::generate(&program(source), "principal").unwrap();
+    assert!(code.contains("int lsi_fat(int n) {"));
+    assert!(code.contains("    int r = 0, m = 0, k = 0;"));
+    assert!(code.contains("    k = RUNTIME_CALL(lsi_fat(m));"));
+    assert!(code.contains("    if (y != 3628800) {"));
+    assert!(code.contains("    if (runtime_sub(x, 10)) {"));
+    assert!(code.contains("printf(\"%d\\n\", y);"));
+    assert_same_behaviour("fatorial", source);
+}
+
+#[test]
+fn reserved_names_are_renamed() {
+    let source = "def main(int double) { int printf, NULL; printf := double * 2; NULL := printf; print NULL; return NULL; }
+        def printf(int stdout) { return stdout; }
+        def principal() { int while, int1; while := 21; int1 := main(while); int1 := printf(int1); print int1; return; }";
+    let code = c::generate(&program(source), "principal").unwrap();
+    assert!(code.contains("int lsi_main(int double_) {"));
+    assert!(code.contains("int printf_ = 0, NULL_ = 0;"));
+    assert!(code.contains("int lsi_printf(int stdout_) {"));
+    assert!(code.contains("int while_ = 0, int1 = 0;"));
+    assert_same_behaviour("reservados", source);
+}
+
+#[test]
+fn statement_program_becomes_principal() {
+    let program = program("{ int x; x := 2 * 21; print x; }");
+    let code = c::generate(&program, "principal").unwrap();
+    assert!(code.contains("int lsi_principal(void) {"));
+    assert!(code.contains("(void)RUNTIME_CALL(lsi_principal());"));
+    assert!(c::generate(&program, "outra").is_err());
+}
+
+#[test]
+fn runtime_errors_stop_the_program() {
+    let errors = assert_same_behaviour(
+        "divisao",
+        "def principal() { int z; print 1; print 1 / z; print 2; return; }",
+    );
+    assert!(errors.is_empty() || errors.contains("divisão por zero"));
+    // Os dois operandos podem falhar: o erro relatado é o do operando da esquerda
+    let errors = assert_same_behaviour(
+        "ordem",
+        "def principal() { int x, z; x := 2147483647; print (x + 1) + 1 / z; return; }",
+    );
+    assert!(errors.is_empty() || errors.contains("estouro aritmético"));
+    assert_same_behaviour(
+        "minimo",
+        "def principal() { int x, y; x := 0 - 2147483647 - 1; y := 0 - 1; print x / y; return; }",
+    );
+    let errors = assert_same_behaviour(
+        "recursao",
+        "def principal() { int b; b := principal(); return b; }",
+    );
+    assert!(errors.is_empty() || errors.contains("limite de 1000 chamadas"));
+}
+
+#[test]
+fn predefined_macros_are_renamed() {
+    let source = "def principal() { int linux, unix, i386; linux := 3; unix := linux + 1; i386 := unix * 2; print linux; print unix; print i386; return; }";
+    let code = c::generate(&program(source), "principal").unwrap();
+    assert!(code.contains("int linux_ = 0, unix_ = 0, i386_ = 0;"));
+    common::assert_same_behaviour(&GNU_C, "macros", source);
+}
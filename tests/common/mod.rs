@@ -0,0 +1,100 @@
+//! Funções compartilhadas pelos testes de integração: análise dos programas de teste e
+//! comparação dos executáveis gerados pelos backends nativos com o interpretador.
+// Cada arquivo de teste usa apenas parte das funções
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use parser::{interpreter, lsi_keywords, parse, semantic, tokenize, Program};
+
+/// Analisa um programa que deve ser léxica, sintática e semanticamente válido.
+pub fn program(source: &str) -> Program {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, diagnostics) = tokenize(source, &keywords, &mut symbol_table);
+    assert!(diagnostics.is_empty(), "erros léxicos: {:?}", diagnostics);
+    let program = parse(tokens).expect("o programa deveria ser sintaticamente válido");
+    let diagnostics = semantic::check(&program);
+    assert!(
+        diagnostics.iter().all(|diagnostic| !diagnostic.is_error()),
+        "erros semânticos: {:?}",
+        diagnostics
+    );
+    program
+}
+
+/// Executa o programa no interpretador a partir de `principal`.
+///
+/// # Retorna
+/// A saída do `print` e se a execução terminou sem erro.
+pub fn interpret(source: &str) -> (String, bool) {
+    let mut out = vec![];
+    let program = program(source);
+    let result = interpreter::on_large_stack(|| interpreter::run(&program, "principal", &mut out));
+    (String::from_utf8(out).unwrap(), result.is_ok())
+}
+
+/// Backend que gera um arquivo compilado pelo `cc` do sistema.
+pub struct Native {
+    pub name: &'static str,      // Nome do backend, usado no diretório temporário
+    pub extension: &'static str, // Extensão do arquivo gerado
+    pub flags: &'static [&'static str], // Opções do `cc`
+    pub generate: fn(&Program) -> String,
+}
+
+/// Saída de um executável.
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: i32,
+}
+
+/// Gera, compila e executa o programa, retornando a sua saída.
+/// Retorna `None` se o `cc` não estiver disponível.
+pub fn run_native(backend: &Native, name: &str, source: &str) -> Option<Output> {
+    if Command::new("cc").arg("--version").output().is_err() {
+        eprintln!("cc não encontrado; teste de ponta a ponta ignorado");
+        return None;
+    }
+
+    let code = (backend.generate)(&program(source));
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("lsi-{}-{}", backend.name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let source_path = dir.join(format!("{}.{}", name, backend.extension));
+    let exe_path = dir.join(name);
+    fs::write(&source_path, code).unwrap();
+
+    let status = Command::new("cc")
+        .args(backend.flags)
+        .arg("-o")
+        .arg(&exe_path)
+        .arg(&source_path)
+        .status()
+        .unwrap();
+    assert!(
+        status.success(),
+        "falha ao compilar {}",
+        source_path.display()
+    );
+
+    let output = Command::new(&exe_path).output().unwrap();
+    Some(Output {
+        stdout: String::from_utf8(output.stdout).unwrap(),
+        stderr: String::from_utf8(output.stderr).unwrap(),
+        code: output.status.code().unwrap(),
+    })
+}
+
+/// Compara a saída do executável com a do interpretador e retorna a saída de erro.
+pub fn assert_same_behaviour(backend: &Native, name: &str, source: &str) -> String {
+    let Some(native) = run_native(backend, name, source) else {
+        return String::new();
+    };
+    let (expected, ok) = interpret(source);
+    assert_eq!(native.stdout, expected);
+    assert_eq!(native.code == 0, ok, "código de saída {}", native.code);
+    native.stderr
+}
@@ -0,0 +1,122 @@
+//! Testes do gerador de analisadores léxicos: expressões regulares, construção e minimização
+//! do AFD, especificações léxicas e o analisador léxico da LSI-2024-2 gerado a partir delas.
+use std::collections::HashSet;
+
+use parser::dfa::{self, Dfa, DEAD};
+use parser::lexer::{lsi_lexer, reconstruct, Lexer, LexerOptions};
+use parser::token::TriviaKind;
+use parser::{lsi_keywords, tokenize};
+
+fn matches(dfa: &Dfa, input: &str) -> bool {
+    dfa.longest_match(input)
+        .is_some_and(|(_, length)| length == input.len())
+}
+
+#[test]
+fn regular_expressions_are_recognized() {
+    let dfa = Dfa::build(&["(a|b)*abb"]).unwrap();
+    for input in ["abb", "aabb", "babb", "ababb"] {
+        assert!(matches(&dfa, input), "{}", input);
+    }
+    for input in ["ab", "abba", "", "c"] {
+        assert!(!matches(&dfa, input), "{}", input);
+    }
+    // O AFD mínimo clássico para `(a|b)*abb` tem 4 estados
+    assert_eq!(dfa.states(), 4);
+
+    let dfa = Dfa::build(&[r"[^a-c\n]+x?", r"\u{e9}\*\s"]).unwrap();
+    assert!(matches(&dfa, "dé!x"));
+    assert!(!matches(&dfa, "da"));
+    assert!(matches(&dfa, "é*\u{a0}"));
+    assert_eq!(dfa.next(0, 'a'), DEAD);
+}
+
+#[test]
+fn longest_match_and_priority() {
+    let dfa = Dfa::build(&["if", "[a-z]+", "<|<=|<>"]).unwrap();
+    assert_eq!(dfa.longest_match("if("), Some((0, 2)));
+    assert_eq!(dfa.longest_match("ifx"), Some((1, 3)));
+    assert_eq!(dfa.longest_match("<=3"), Some((2, 2)));
+    assert_eq!(dfa.longest_match("<3"), Some((2, 1)));
+    assert_eq!(dfa.longest_match("3"), None);
+
+    // Volta ao último estado de aceitação quando o lexema mais longo não se completa
+    let dfa = Dfa::build(&["a", "abc"]).unwrap();
+    assert_eq!(dfa.longest_match("abd"), Some((0, 1)));
+}
+
+#[test]
+fn invalid_expressions_are_reported() {
+    let error = |pattern| Dfa::build(&["a", pattern]).unwrap_err();
+    assert_eq!(error("(ab").message, "`(` sem o `)` correspondente");
+    assert_eq!(error("ab)").position, 2);
+    assert_eq!(error("*a").message, "`*` sem operando");
+    assert_eq!(error("[z-a]").message, "intervalo de caracteres inválido");
+    assert_eq!(error("[ab").rule, 1);
+    assert_eq!(
+        error("a*").message,
+        "a expressão regular aceita a cadeia vazia"
+    );
+}
+
+#[test]
+fn specifications_are_validated() {
+    let rules = dfa::parse_spec("# comentário\n\nId  [a-z]+\nInt [0-9]+\n").unwrap();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[1].pattern, "[0-9]+");
+    assert_eq!((rules[1].span.line, rules[1].span.column), (4, 5));
+
+    let diagnostics = dfa::parse_spec("Id [a-z]+\nId [A-Z]+\nInt").unwrap_err();
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics[0].message.contains("mais de uma vez"));
+    assert!(diagnostics[1]
+        .message
+        .contains("não tem uma expressão regular"));
+
+    let diagnostics = Lexer::from_spec("Id [a-z]+\nInt [0-9](\n").unwrap_err();
+    assert_eq!(
+        diagnostics[0].message,
+        "expressão regular inválida na regra `Int`: `(` sem o `)` correspondente"
+    );
+    assert_eq!(
+        (diagnostics[0].span.line, diagnostics[0].span.column),
+        (2, 10)
+    );
+
+    let diagnostics = Lexer::from_spec("Numero [0-9]+").unwrap_err();
+    assert!(diagnostics[0].message.contains("`Numero` desconhecida"));
+}
+
+#[test]
+fn the_lsi_lexer_is_a_single_table() {
+    let dfa = lsi_lexer().dfa();
+    assert!(dfa.states() < 64, "{} estados", dfa.states());
+    assert!(dfa.classes() < 64, "{} classes", dfa.classes());
+
+    let keywords = lsi_keywords();
+    let mut symbol_table: HashSet<String> = keywords.clone();
+    let source = "def f(int a) {\n  /* x */ a := 12abc + a<>3; # fim\n}";
+    let (tokens, diagnostics) = tokenize(source, &keywords, &mut symbol_table);
+    let lexemes: Vec<&str> = tokens.iter().map(|token| token.lexeme.as_str()).collect();
+    assert_eq!(
+        lexemes,
+        ["def", "f", "(", "int", "a", ")", "{", "a", ":=", "+", "a", "<>", "3", ";", "}"]
+    );
+    assert_eq!(reconstruct(&tokens), source);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Some("E0006"));
+    assert_eq!(diagnostics[0].message, "número mal formado `12abc`");
+    assert_eq!(tokens[9].leading_trivia[1].kind, TriviaKind::Skipped);
+
+    // Um analisador léxico gerado a partir de outra especificação
+    let lexer = Lexer::from_spec("Whitespace \\s+\nId [a-z]+\nBracket \\[|\\]").unwrap();
+    let (tokens, diagnostics) = lexer.tokenize(
+        "[ab ]",
+        &HashSet::new(),
+        &mut HashSet::new(),
+        LexerOptions::default(),
+    );
+    assert!(diagnostics.is_empty());
+    assert_eq!(tokens[0].terminal_name(), "[");
+    assert_eq!(tokens[1].terminal_name(), "id");
+}
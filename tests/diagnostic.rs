@@ -0,0 +1,130 @@
+//! Testes dos diagnósticos: códigos e rótulos produzidos pelas análises e a renderização
+//! com as linhas do código fonte.
+use parser::diagnostic::{LabelStyle, Renderer};
+use parser::lexer::{tokenize_with_options, LexerOptions};
+use parser::{lsi_keywords, parse, semantic, tokenize, Diagnostic, Span};
+
+fn lex(source: &str) -> Vec<Diagnostic> {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    tokenize(source, &keywords, &mut symbol_table).1
+}
+
+fn render(source: &str, diagnostic: &Diagnostic) -> String {
+    Renderer::new(source, "programa.lsi", false).render(diagnostic)
+}
+
+#[test]
+fn lexical_errors_have_codes_and_labels() {
+    let source = "def principal() {\n    print @;\n}";
+    let diagnostics = lex(source);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Some("E0001"));
+    assert_eq!(
+        render(source, &diagnostics[0]),
+        "\
+erro[E0001]: caractere não identificado '@'
+ --> programa.lsi:2:11
+  |
+2 |     print @;
+  |           ^ caractere inválido na LSI-2024-2
+"
+    );
+}
+
+#[test]
+fn suggestions_show_the_fixed_line() {
+    let source = "if (a != b) print a;";
+    let diagnostics = lex(source);
+    assert_eq!(diagnostics[0].code, Some("E0003"));
+    assert_eq!(
+        render(source, &diagnostics[0]),
+        "\
+erro[E0003]: operador `!=` inválido; o operador diferente da LSI-2024-2 é `<>`
+ --> programa.lsi:1:7
+  |
+1 | if (a != b) print a;
+  |       ^^ operador inválido
+  |
+  = nota: a opção --compat-ne aceita `!=` com um aviso
+  = sugestão: use `<>`
+  |
+1 | if (a <> b) print a;
+  |       ~~
+"
+    );
+
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let options = LexerOptions {
+        compat_not_equal: true,
+        ..LexerOptions::default()
+    };
+    let (_, diagnostics) = tokenize_with_options(source, &keywords, &mut symbol_table, options);
+    assert_eq!(diagnostics[0].code, Some("W0001"));
+    assert!(render(source, &diagnostics[0]).starts_with("aviso[W0001]: "));
+}
+
+#[test]
+fn syntax_errors_are_rendered_through_diagnostics() {
+    let source = "def principal() {\n  print 1\n}";
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, _) = tokenize(source, &keywords, &mut symbol_table);
+    let errors = parse(tokens).unwrap_err();
+    let diagnostic = errors[0].to_diagnostic();
+    assert_eq!(diagnostic.code, Some("E0100"));
+    assert_eq!(diagnostic.message, errors[0].message);
+    assert!(render(source, &diagnostic).contains("3 | }\n  | ^ `}` inesperado\n"));
+
+    let source = "def principal() {";
+    let (tokens, _) = tokenize(source, &keywords, &mut symbol_table);
+    let diagnostic = parse(tokens).unwrap_err()[0].to_diagnostic();
+    assert!(render(source, &diagnostic).contains(
+        "1 | def principal() {\n  |                  ^ fim da entrada\n  |\n  = nota: o arquivo terminou antes do fim do programa\n"
+    ));
+}
+
+#[test]
+fn secondary_labels_point_to_other_lines() {
+    let source = "def f(int a) {\n  int b;\n\n\n  int b;\n  return b;\n}";
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, _) = tokenize(source, &keywords, &mut symbol_table);
+    let diagnostics = semantic::check(&parse(tokens).unwrap());
+    assert_eq!(diagnostics[0].code, Some("E0201"));
+    assert_eq!(diagnostics[0].labels[1].style, LabelStyle::Secondary);
+    assert_eq!(
+        render(source, &diagnostics[0]),
+        "\
+erro[E0201]: `b` já foi declarado neste escopo na linha 2, coluna 7
+ --> programa.lsi:5:7
+  |
+2 |   int b;
+  |       - primeira declaração
+...
+5 |   int b;
+  |       ^ declarado novamente aqui
+"
+    );
+}
+
+#[test]
+fn tabs_are_expanded_and_colors_are_optional() {
+    let source = "\tx := 1;";
+    let span = Span {
+        start: 1,
+        end: 2,
+        line: 1,
+        column: 2,
+    };
+    let diagnostic = Diagnostic::warning("atenção", span).with_label("aqui");
+    assert_eq!(
+        render(source, &diagnostic),
+        "aviso: atenção\n --> programa.lsi:1:2\n  |\n1 |     x := 1;\n  |     ^ aqui\n"
+    );
+
+    let colored = Renderer::new(source, "programa.lsi", true).render(&diagnostic);
+    assert!(colored.starts_with("\x1b[1;33maviso\x1b[0m\x1b[1m: atenção\x1b[0m\n"));
+    assert!(colored.contains("\x1b[1;33m^ aqui\x1b[0m"));
+}
@@ -0,0 +1,230 @@
+//! Testes da construção da tabela LL(1): conjuntos FIRST e FOLLOW calculados a partir das
+//! produções, os conflitos LL(1) encontrados na tabela e a seleção com lookahead estendido.
+use std::collections::BTreeSet;
+
+use parser::ast::{Expr, Stmt};
+use parser::grammar::{ConflictKind, Grammar, Resolution, END_MARKER, EPSILON};
+use parser::ll1_table::{ll1_transition_table, lsi_grammar};
+use parser::{lsi_keywords, parse, tokenize, Program};
+
+fn set(symbols: &[&str]) -> BTreeSet<String> {
+    symbols.iter().map(|symbol| symbol.to_string()).collect()
+}
+
+/// Gramática de expressões sem recursão à esquerda, do livro do dragão.
+fn expression_grammar() -> Grammar {
+    let mut grammar = Grammar::new("E");
+    grammar.add("E", &["T E'"]);
+    grammar.add("E'", &["+ T E'", "ε"]);
+    grammar.add("T", &["F T'"]);
+    grammar.add("T'", &["* F T'", "ε"]);
+    grammar.add("F", &["( E )", "id"]);
+    grammar
+}
+
+#[test]
+fn first_and_follow_of_the_expression_grammar() {
+    let grammar = expression_grammar();
+    let first = grammar.first_sets();
+    assert_eq!(first["E"], set(&["(", "id"]));
+    assert_eq!(first["T"], set(&["(", "id"]));
+    assert_eq!(first["E'"], set(&["+", EPSILON]));
+    assert_eq!(first["T'"], set(&["*", EPSILON]));
+
+    let follow = grammar.follow_sets(&first);
+    assert_eq!(follow["E"], set(&[")", END_MARKER]));
+    assert_eq!(follow["E'"], set(&[")", END_MARKER]));
+    assert_eq!(follow["T"], set(&["+", ")", END_MARKER]));
+    assert_eq!(follow["T'"], set(&["+", ")", END_MARKER]));
+    assert_eq!(follow["F"], set(&["+", "*", ")", END_MARKER]));
+
+    // FIRST de uma sequência para no primeiro símbolo que não deriva ε
+    let sequence = ["T'".to_string(), "E'".to_string(), ")".to_string()];
+    assert_eq!(grammar.first_of(&sequence, &first), set(&["*", "+", ")"]));
+    assert_eq!(
+        grammar.first_of(&sequence[..2], &first),
+        set(&["*", "+", EPSILON])
+    );
+}
+
+#[test]
+fn first_and_follow_of_lsi_nonterminals() {
+    let grammar = lsi_grammar();
+    let first = grammar.first_sets();
+    let follow = grammar.follow_sets(&first);
+    let relops = ["<", "<=", ">", ">=", "==", "<>"];
+
+    assert_eq!(first["EXPR"], set(&["(", "id", "num"]));
+    assert_eq!(first["ATRIBST'"], set(&["(", "id", "num"]));
+    assert_eq!(first["FCALL"], set(&["id"]));
+    assert_eq!(first["RETURNST'"], set(&["id", EPSILON]));
+    let mut expr_tail = set(&relops);
+    expr_tail.insert(EPSILON.to_string());
+    assert_eq!(first["EXPR'"], expr_tail);
+
+    assert_eq!(follow["EXPR"], set(&[")", ";"]));
+    assert_eq!(follow["EXPR'"], set(&[")", ";"]));
+    assert_eq!(follow["ATRIBST'"], set(&[";"]));
+    assert_eq!(follow["VARLIST'"], set(&[";"]));
+    assert_eq!(follow["PARLIST"], set(&[")"]));
+    let mut numexpr = set(&relops);
+    numexpr.extend(set(&[")", ";"]));
+    assert_eq!(follow["NUMEXPR"], numexpr);
+    assert_eq!(follow["MAIN"], set(&[END_MARKER]));
+}
+
+/// Gramática do `if` com `else` opcional (dangling else) e uma condição ambígua.
+fn ambiguous_grammar() -> Grammar {
+    let mut grammar = Grammar::new("S");
+    grammar.add("S", &["i E t S S'", "a"]);
+    grammar.add("S'", &["e S", "ε"]);
+    grammar.add("E", &["b", "b c"]);
+    grammar
+}
+
+#[test]
+fn conflicts_are_reported_with_their_kind() {
+    assert!(expression_grammar().conflicts().is_empty());
+
+    let conflicts = ambiguous_grammar().conflicts();
+    let summary: Vec<(ConflictKind, &str, &str, Vec<String>)> = conflicts
+        .iter()
+        .map(|conflict| {
+            (
+                conflict.kind,
+                conflict.nonterminal.as_str(),
+                conflict.terminal.as_str(),
+                conflict
+                    .productions
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (
+                ConflictKind::FirstFollow,
+                "S'",
+                "e",
+                vec!["S' -> e S".to_string(), "S' -> ε".to_string()]
+            ),
+            (
+                ConflictKind::FirstFirst,
+                "E",
+                "b",
+                vec!["E -> b".to_string(), "E -> b c".to_string()]
+            ),
+        ]
+    );
+    assert!(conflicts
+        .iter()
+        .all(|conflict| matches!(conflict.resolution, Resolution::DeclarationOrder)));
+    assert_eq!(
+        conflicts[1].to_string(),
+        "Conflito FIRST/FIRST em M[E, b] (resolvido pela ordem de declaração):\n    E -> b  (padrão)\n    E -> b c"
+    );
+}
+
+#[test]
+fn preferences_choose_the_default_production() {
+    let mut grammar = ambiguous_grammar();
+    grammar.prefer("E", "b", "b c");
+    let table = grammar.ll1_table();
+    assert_eq!(
+        table.conflicts.len(),
+        2,
+        "a preferência não remove o conflito"
+    );
+
+    let conflict = &table.conflicts[1];
+    assert!(matches!(conflict.resolution, Resolution::Preference));
+    assert_eq!(conflict.productions[0].to_string(), "E -> b c");
+    assert_eq!(table.get("E", "b").unwrap().to_string(), "E -> b c");
+    assert_eq!(table.get("S'", "e").unwrap().to_string(), "S' -> e S");
+    assert_eq!(table.get("S'", END_MARKER).unwrap().to_string(), "S' -> ε");
+}
+
+#[test]
+fn lsi_conflicts_are_all_resolved_by_directives() {
+    let conflicts = lsi_grammar().conflicts();
+    let entries: Vec<(ConflictKind, &str, &str)> = conflicts
+        .iter()
+        .map(|conflict| {
+            (
+                conflict.kind,
+                conflict.nonterminal.as_str(),
+                conflict.terminal.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            (ConflictKind::FirstFirst, "MAIN", "$"),
+            (ConflictKind::FirstFirst, "ATRIBST'", "id"),
+            (ConflictKind::FirstFollow, "IFSTMT'", "else"),
+            (ConflictKind::FirstFirst, "STMTLIST'", "}"),
+        ]
+    );
+    assert!(conflicts
+        .iter()
+        .all(|conflict| !matches!(conflict.resolution, Resolution::DeclarationOrder)));
+}
+
+fn program(source: &str) -> Program {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, _) = tokenize(source, &keywords, &mut symbol_table);
+    parse(tokens).expect("o programa deveria ser sintaticamente válido")
+}
+
+/// Valor atribuído pelo primeiro comando da primeira função.
+fn assigned_value(program: &Program) -> &Expr {
+    match &program.functions[0].body[0] {
+        Stmt::Assign { value, .. } => value,
+        stmt => panic!("comando inesperado: {:?}", stmt),
+    }
+}
+
+#[test]
+fn two_tokens_of_lookahead_separate_calls_from_expressions() {
+    let table = ll1_transition_table();
+    assert_eq!(table.lookahead_depth(), 2);
+
+    let select = |lookahead: &[&str]| table.select("ATRIBST'", lookahead).unwrap().to_string();
+    assert_eq!(select(&["id", "("]), "ATRIBST' -> FCALL");
+    for next in [";", "+", "*", "<>"] {
+        assert_eq!(select(&["id", next]), "ATRIBST' -> EXPR", "id {}", next);
+    }
+    assert_eq!(select(&["(", "id"]), "ATRIBST' -> EXPR");
+    assert_eq!(select(&["num", ";"]), "ATRIBST' -> EXPR");
+    assert_eq!(
+        table.get("ATRIBST'", "id").unwrap().to_string(),
+        "ATRIBST' -> EXPR"
+    );
+
+    match assigned_value(&program("def principal() { x := f(a, b); }")) {
+        Expr::Call(call) => {
+            assert_eq!(call.name.name, "f");
+            assert_eq!(call.args.len(), 2);
+        }
+        value => panic!("esperada uma chamada: {:?}", value),
+    }
+    assert!(matches!(
+        assigned_value(&program("def principal() { x := f; }")),
+        Expr::Var(name) if name.name == "f"
+    ));
+    assert!(matches!(
+        assigned_value(&program("def principal() { x := f + 1; }")),
+        Expr::Binary { .. }
+    ));
+}
+
+#[test]
+fn the_lsi_grammar_and_table_are_built_once() {
+    assert!(std::ptr::eq(lsi_grammar(), lsi_grammar()));
+    assert!(std::ptr::eq(ll1_transition_table(), ll1_transition_table()));
+}
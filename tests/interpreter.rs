@@ -0,0 +1,186 @@
+//! Testes do interpretador: execução a partir da função de entrada, saída do `print` e erros
+//! de execução, pela biblioteca e pelo subcomando `interpret`.
+mod common;
+
+use std::process::{Command, Output};
+
+use parser::interpreter::{self, RuntimeError, DEFAULT_ENTRY};
+
+use common::program;
+
+/// Executa o programa a partir de `entry` e retorna a saída do `print` e o resultado.
+fn execute(source: &str, entry: &str) -> (String, Result<i32, RuntimeError>) {
+    let program = program(source);
+    let mut out = vec![];
+    let result = interpreter::on_large_stack(|| interpreter::run(&program, entry, &mut out));
+    (String::from_utf8(out).unwrap(), result)
+}
+
+/// Saída do `print` e o valor retornado ou a mensagem do erro de execução.
+fn run(source: &str, entry: &str) -> (String, Result<i32, String>) {
+    let (output, result) = execute(source, entry);
+    (output, result.map_err(|error| error.message))
+}
+
+/// Saída anterior ao erro de execução do programa, e a mensagem, linha e coluna do erro.
+fn runtime_error(source: &str) -> (String, String, usize, usize) {
+    let (output, result) = execute(source, DEFAULT_ENTRY);
+    let error = result.expect_err("a execução deveria falhar");
+    (output, error.message, error.span.line, error.span.column)
+}
+
+/// Executa `parser interpret` com os argumentos `args` sobre o programa `source`.
+fn interpret_command(name: &str, source: &str, args: &[&str]) -> Output {
+    let path = std::env::temp_dir().join(format!("lsi-{}-{}.lsi", name, std::process::id()));
+    std::fs::write(&path, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_parser"))
+        .arg("interpret")
+        .args(args)
+        .arg(&path)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    output
+}
+
+#[test]
+fn deep_recursion_stops_at_the_call_limit() {
+    // Cada nível de recursão passa por blocos e `if` aninhados, que também usam a pilha
+    let source = "def desce(int n) {
+            int r, m;
+            if (n <= 0) { r := 0; return r; }
+            { { if (n > 0) { { m := n - 1; r := desce(m); } } } }
+            r := r + 1;
+            return r;
+        }
+        def principal() {
+            int n, r;
+            n := 998;
+            r := desce(n);
+            print r;
+            n := 999;
+            r := desce(n);
+            print r;
+            return;
+        }";
+    for args in [&[][..], &["--vm"][..]] {
+        let output = interpret_command("recursao", source, args);
+        assert_eq!(output.status.code(), Some(1), "{:?}", args);
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "998\n");
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains("limite de 1000 chamadas aninhadas excedido ao chamar `desce`"),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn programs_start_at_the_entry_function() {
+    let source = "def outra() { int x; x := 7; print x; return x; }
+        def principal() { print 1; return; }";
+    assert_eq!(run(source, DEFAULT_ENTRY), ("1\n".to_string(), Ok(0)));
+    assert_eq!(run(source, "outra"), ("7\n".to_string(), Ok(7)));
+
+    assert_eq!(
+        run(source, "inexistente").1,
+        Err("a função de entrada `inexistente` não foi definida".to_string())
+    );
+    assert_eq!(
+        run("def f(int a) { return a; }", "f").1,
+        Err("a função de entrada `f` não pode receber parâmetros".to_string())
+    );
+
+    // Um programa formado por um único comando é executado sem função de entrada
+    assert_eq!(
+        run("{ int x; x := 6 * 7; print x; }", DEFAULT_ENTRY),
+        ("42\n".to_string(), Ok(0))
+    );
+}
+
+#[test]
+fn statements_and_expressions() {
+    let source = "def principal() {
+            int x, y;
+            print x;
+            x := 10;
+            y := (x + 2) * 3 - 4 / 2;
+            print y;
+            print 7 / 2;
+            print 0 - 7 / 2;
+            print x >= 10;
+            print x <> 10;
+            if (x - 10) print 1; else print 2;
+            if (x == 10) { int x; x := 5; print x; } else print 0;
+            print x;
+            if (y < 0) print 3;
+            ;
+            return;
+        }";
+    assert_eq!(
+        run(source, DEFAULT_ENTRY),
+        ("0\n34\n3\n-3\n1\n0\n2\n5\n10\n".to_string(), Ok(0))
+    );
+}
+
+#[test]
+fn arguments_are_passed_by_value() {
+    let source = "def fat(int n) {
+            int r, m, k;
+            if (n <= 1) { r := 1; return r; }
+            m := n - 1;
+            k := fat(m);
+            r := n * k;
+            return r;
+        }
+        def altera(int a, int b) { a := a - b; print a; return a; }
+        def principal() {
+            int x, y, z;
+            x := 10;
+            y := fat(x);
+            print y;
+            y := 3;
+            z := altera(x, y);
+            print x;
+            print z;
+            return;
+        }";
+    assert_eq!(
+        run(source, DEFAULT_ENTRY),
+        ("3628800\n7\n10\n7\n".to_string(), Ok(0))
+    );
+}
+
+#[test]
+fn runtime_errors_stop_the_program() {
+    assert_eq!(
+        runtime_error("def principal() {\n  int z;\n  print 1;\n  print 1 / z;\n  print 2;\n}"),
+        ("1\n".to_string(), "divisão por zero".to_string(), 4, 9)
+    );
+    assert_eq!(
+        runtime_error("def principal() {\n  int x;\n  x := 2147483647;\n  x := x + 1;\n}"),
+        (
+            String::new(),
+            "estouro aritmético em `2147483647 + 1`".to_string(),
+            4,
+            8
+        )
+    );
+    assert_eq!(
+        runtime_error(
+            "def principal() {\n  int x;\n  x := 0 - 2147483647 - 1;\n  x := x / (0 - 1);\n}"
+        )
+        .1,
+        "estouro aritmético em `-2147483648 / -1`"
+    );
+    assert_eq!(
+        runtime_error("def principal() {\n  int b;\n  b := principal();\n  return b;\n}"),
+        (
+            String::new(),
+            "limite de 1000 chamadas aninhadas excedido ao chamar `principal`".to_string(),
+            3,
+            8
+        )
+    );
+}
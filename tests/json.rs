@@ -0,0 +1,91 @@
+//! Testes da saída em JSON: escrita dos valores, conversão de tokens e diagnósticos
+//! e o documento impresso por `--format json`.
+use std::process::Command;
+
+use parser::json::{self, Json};
+use parser::{lsi_keywords, parse, tokenize, Diagnostic, Span};
+
+#[test]
+fn values_are_written_on_a_single_line() {
+    let value = Json::object([
+        ("null", Json::Null),
+        ("bool", true.into()),
+        ("number", (-42).into()),
+        ("list", [1, 2].into_iter().collect()),
+        ("empty", Json::object([])),
+    ]);
+    assert_eq!(
+        value.to_string(),
+        r#"{"null":null,"bool":true,"number":-42,"list":[1,2],"empty":{}}"#
+    );
+    assert_eq!(value.get("number"), Some(&Json::Number(-42)));
+    assert_eq!(value.get("missing"), None);
+}
+
+#[test]
+fn strings_are_escaped() {
+    let value: Json = "aspas \" barra \\ linha\n tab\t\u{1} ação".into();
+    assert_eq!(
+        value.to_string(),
+        r#""aspas \" barra \\ linha\n tab\t\u0001 ação""#
+    );
+}
+
+#[test]
+fn tokens_and_diagnostics_are_converted() {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, _) = tokenize("x := 42;", &keywords, &mut symbol_table);
+    assert_eq!(
+        json::token(&tokens[2]).to_string(),
+        r#"{"type":"Int","lexeme":"42","value":{"kind":"number","value":42},"terminal":"num","span":{"start":5,"end":7,"line":1,"column":6}}"#
+    );
+
+    let span = Span {
+        start: 0,
+        end: 1,
+        line: 1,
+        column: 1,
+    };
+    let diagnostic = Diagnostic::warning("cuidado", span)
+        .with_code("W0001")
+        .with_label("aqui")
+        .with_note("nota")
+        .with_suggestion("troque", span, "y");
+    assert_eq!(
+        json::diagnostic(&diagnostic, "lexical").to_string(),
+        concat!(
+            r#"{"stage":"lexical","severity":"warning","code":"W0001","message":"cuidado","#,
+            r#""span":{"start":0,"end":1,"line":1,"column":1},"#,
+            r#""labels":[{"style":"primary","span":{"start":0,"end":1,"line":1,"column":1},"message":"aqui"}],"#,
+            r#""notes":["nota"],"#,
+            r#""suggestions":[{"message":"troque","span":{"start":0,"end":1,"line":1,"column":1},"replacement":"y"}]}"#
+        )
+    );
+
+    let (tokens, _) = tokenize("print 1", &keywords, &mut symbol_table);
+    let error = json::syntax_error(&parse(tokens).unwrap_err()[0]);
+    assert_eq!(error.get("stage"), Some(&"syntax".into()));
+    assert_eq!(error.get("code"), Some(&"E0100".into()));
+    assert_eq!(error.get("found"), Some(&Json::Null));
+}
+
+#[test]
+fn format_json_prints_a_single_document() {
+    let path = std::env::temp_dir().join(format!("lsi-json-{}.lsi", std::process::id()));
+    std::fs::write(&path, "def principal() {\n  int a b;\n}").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_parser"))
+        .args(["--format", "json"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(stdout.starts_with(r#"{"file":""#));
+    assert!(stdout.contains(r#""success":false,"tokens":[{"type":"Keyword","lexeme":"def""#));
+    assert!(stdout.contains(r#""expected":[",",";"],"found":"b""#));
+    assert!(stdout.ends_with("\"parse\":{\"success\":false,\"errors\":1}}\n"));
+}
@@ -0,0 +1,246 @@
+//! Testes do analisador léxico: posição dos tokens, caracteres inválidos, comentários, trivia
+//! anexada aos tokens e reconstrução do código fonte.
+use std::collections::HashSet;
+
+use parser::ast::Stmt;
+use parser::lexer::reconstruct;
+use parser::token::{Trivia, TriviaKind};
+use parser::{lsi_keywords, parse, tokenize, Diagnostic, Token};
+
+fn lex(source: &str) -> (Vec<Token>, Vec<Diagnostic>) {
+    let keywords = lsi_keywords();
+    let mut symbol_table: HashSet<String> = keywords.clone();
+    tokenize(source, &keywords, &mut symbol_table)
+}
+
+fn lexemes(tokens: &[Token]) -> Vec<&str> {
+    tokens.iter().map(|token| token.lexeme.as_str()).collect()
+}
+
+fn trivia(trivia: &[Trivia]) -> Vec<(TriviaKind, &str)> {
+    trivia
+        .iter()
+        .map(|trivia| (trivia.kind, trivia.text.as_str()))
+        .collect()
+}
+
+#[test]
+fn reconstruct_reproduces_the_source() {
+    for source in [
+        "def principal() { int x; x := 1; print x; }",
+        "  \t\n{ int x;\n\n  x := 2; }\n\n",
+        "# cabeçalho\n{ /* bloco\n em duas linhas */ print 1; # fim\n}# sem quebra",
+        "{ int x; x := 1 @ 2 $ é; }",
+        "print 1;\r\n/* depois */  \n",
+        "print 1; /* sem fim\n print 2;",
+        "print 12abc;",
+    ] {
+        let (tokens, _) = lex(source);
+        assert!(!tokens.is_empty(), "{:?}", source);
+        assert_eq!(reconstruct(&tokens), source);
+    }
+}
+
+#[test]
+fn comments_are_trivia() {
+    let (tokens, diagnostics) = lex("# print 1;\nprint /* 2; */ 3; /**/ /* a ** b * / */");
+    assert!(diagnostics.is_empty());
+    assert_eq!(lexemes(&tokens), ["print", "3", ";"]);
+    assert_eq!(
+        trivia(&tokens[0].leading_trivia),
+        [
+            (TriviaKind::LineComment, "# print 1;"),
+            (TriviaKind::Whitespace, "\n"),
+        ]
+    );
+    assert_eq!(
+        trivia(&tokens[1].leading_trivia),
+        [
+            (TriviaKind::Whitespace, " "),
+            (TriviaKind::BlockComment, "/* 2; */"),
+            (TriviaKind::Whitespace, " "),
+        ]
+    );
+    assert_eq!(
+        trivia(&tokens[2].trailing_trivia),
+        [
+            (TriviaKind::Whitespace, " "),
+            (TriviaKind::BlockComment, "/**/"),
+            (TriviaKind::Whitespace, " "),
+            (TriviaKind::BlockComment, "/* a ** b * / */"),
+        ]
+    );
+
+    // `#` dentro de um comentário de bloco e `/*` dentro de um comentário de linha
+    let (tokens, diagnostics) = lex("/* # */ print 1; # /* \nprint 2;");
+    assert!(diagnostics.is_empty());
+    assert_eq!(lexemes(&tokens), ["print", "1", ";", "print", "2", ";"]);
+    assert_eq!(
+        trivia(&tokens[3].leading_trivia),
+        [
+            (TriviaKind::Whitespace, " "),
+            (TriviaKind::LineComment, "# /* "),
+            (TriviaKind::Whitespace, "\n"),
+        ]
+    );
+}
+
+#[test]
+fn unterminated_block_comment_is_an_error() {
+    let source = "print 1;\n  /* sem fim\nprint 2;";
+    let (tokens, diagnostics) = lex(source);
+    assert_eq!(lexemes(&tokens), ["print", "1", ";"]);
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.code, Some("E0002"));
+    assert!(diagnostic.is_error());
+    assert_eq!(
+        diagnostic.message,
+        "comentário de bloco não terminado; esperado `*/` antes do fim do arquivo"
+    );
+    assert_eq!(
+        (
+            diagnostic.span.line,
+            diagnostic.span.column,
+            diagnostic.span.start,
+            diagnostic.span.end
+        ),
+        (2, 3, 11, 13)
+    );
+    assert_eq!(
+        trivia(&tokens[2].trailing_trivia),
+        [
+            (TriviaKind::Whitespace, "\n  "),
+            (TriviaKind::BlockComment, "/* sem fim\nprint 2;"),
+        ]
+    );
+    assert_eq!(reconstruct(&tokens), source);
+}
+
+#[test]
+fn trivia_attaches_to_the_following_token() {
+    let source = "  x /* a */ :=\n1 @ ;  # fim\n";
+    let (tokens, diagnostics) = lex(source);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(lexemes(&tokens), ["x", ":=", "1", ";"]);
+
+    let leading: Vec<_> = tokens
+        .iter()
+        .map(|token| trivia(&token.leading_trivia))
+        .collect();
+    assert_eq!(
+        leading,
+        [
+            vec![(TriviaKind::Whitespace, "  ")],
+            vec![
+                (TriviaKind::Whitespace, " "),
+                (TriviaKind::BlockComment, "/* a */"),
+                (TriviaKind::Whitespace, " "),
+            ],
+            vec![(TriviaKind::Whitespace, "\n")],
+            vec![
+                (TriviaKind::Whitespace, " "),
+                (TriviaKind::Skipped, "@"),
+                (TriviaKind::Whitespace, " "),
+            ],
+        ]
+    );
+
+    // Apenas o último token guarda a trivia que o segue
+    for token in &tokens[..3] {
+        assert!(token.trailing_trivia.is_empty(), "{:?}", token.lexeme);
+    }
+    assert_eq!(
+        trivia(&tokens[3].trailing_trivia),
+        [
+            (TriviaKind::Whitespace, "  "),
+            (TriviaKind::LineComment, "# fim"),
+            (TriviaKind::Whitespace, "\n"),
+        ]
+    );
+
+    // A trivia guarda a sua posição no código fonte
+    let comment = &tokens[1].leading_trivia[1];
+    assert_eq!(&source[comment.span.start..comment.span.end], "/* a */");
+    assert_eq!((comment.span.line, comment.span.column), (1, 5));
+    let skipped = &tokens[3].leading_trivia[1];
+    assert_eq!((skipped.span.line, skipped.span.column), (2, 3));
+}
+
+#[test]
+fn every_invalid_character_is_reported_and_parsing_continues() {
+    let source = "{\n  int x@;\n  x := 1 $+ 2;\n  print é x; ~\n}";
+    let (tokens, diagnostics) = lex(source);
+    let errors: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.code,
+                diagnostic.message.as_str(),
+                diagnostic.span.line,
+                diagnostic.span.column,
+            )
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (Some("E0001"), "caractere não identificado '@'", 2, 8),
+            (Some("E0001"), "caractere não identificado '$'", 3, 10),
+            (Some("E0001"), "caractere não identificado 'é'", 4, 9),
+            (Some("E0001"), "caractere não identificado '~'", 4, 14),
+        ]
+    );
+
+    // Os caracteres inválidos são descartados, e os tokens restantes formam um programa válido
+    assert_eq!(
+        lexemes(&tokens),
+        ["{", "int", "x", ";", "x", ":=", "1", "+", "2", ";", "print", "x", ";", "}"]
+    );
+    let program = parse(tokens).expect("os tokens restantes deveriam ser analisados");
+    assert!(matches!(program.statement, Some(Stmt::Block(ref stmts)) if stmts.len() == 3));
+
+    // Um erro sintático após um caractere inválido também é reportado
+    let (tokens, diagnostics) = lex("{ print @ ; print 1 2; }");
+    assert_eq!(diagnostics.len(), 1);
+    let errors = parse(tokens).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].found.as_deref(), Some(";"));
+    assert_eq!(errors[1].found.as_deref(), Some("2"));
+}
+
+#[test]
+fn tokens_carry_their_spans() {
+    let source = "x := 10;\n/* é */ print\tx <> 1;";
+    let (tokens, diagnostics) = lex(source);
+    assert!(diagnostics.is_empty());
+    let spans: Vec<_> = tokens
+        .iter()
+        .map(|token| {
+            let span = token.span;
+            assert_eq!(&source[span.start..span.end], token.lexeme);
+            (
+                token.lexeme.as_str(),
+                span.start,
+                span.end,
+                span.line,
+                span.column,
+            )
+        })
+        .collect();
+    // As colunas contam caracteres, e os offsets contam bytes: `é` ocupa dois bytes
+    assert_eq!(
+        spans,
+        [
+            ("x", 0, 1, 1, 1),
+            (":=", 2, 4, 1, 3),
+            ("10", 5, 7, 1, 6),
+            (";", 7, 8, 1, 8),
+            ("print", 18, 23, 2, 9),
+            ("x", 24, 25, 2, 15),
+            ("<>", 26, 28, 2, 17),
+            ("1", 29, 30, 2, 20),
+            (";", 30, 31, 2, 21),
+        ]
+    );
+}
@@ -0,0 +1,151 @@
+//! Literais inteiros: intervalo de cada largura, zeros à esquerda e o limite de 32 bits da
+//! análise semântica.
+use std::collections::HashSet;
+use std::process::Command;
+
+use parser::ast::{Expr, Stmt};
+use parser::lexer::{tokenize_with_options, IntegerWidth, LexerOptions};
+use parser::token::TokenValue;
+use parser::{lsi_keywords, parse, semantic, Diagnostic, Number, Program, Token};
+
+fn lex(source: &str, integer_width: IntegerWidth) -> (Vec<Token>, Vec<Diagnostic>) {
+    let keywords = lsi_keywords();
+    let mut symbol_table: HashSet<String> = keywords.clone();
+    let options = LexerOptions {
+        integer_width,
+        ..LexerOptions::default()
+    };
+    tokenize_with_options(source, &keywords, &mut symbol_table, options)
+}
+
+fn number(token: &Token) -> &Number {
+    match &token.value {
+        TokenValue::Number(number) => number,
+        value => panic!("token sem valor numérico: {:?}", value),
+    }
+}
+
+#[test]
+fn out_of_range_literals_are_lexical_errors() {
+    let (tokens, diagnostics) = lex("print 2147483647;", IntegerWidth::I32);
+    assert!(diagnostics.is_empty());
+    assert_eq!(number(&tokens[1]), &Number::I32(i32::MAX));
+
+    let (tokens, diagnostics) = lex("print\n  99999999999;", IntegerWidth::I32);
+    assert_eq!(tokens.len(), 3, "o literal continua sendo um token");
+    assert_eq!(number(&tokens[1]), &Number::I32(i32::MAX));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Some("E0004"));
+    assert_eq!(
+        diagnostics[0].message,
+        "literal inteiro `99999999999` fora do intervalo dos inteiros de 32 bits"
+    );
+    assert_eq!(
+        (diagnostics[0].span.line, diagnostics[0].span.column),
+        (2, 3)
+    );
+}
+
+#[test]
+fn leading_zeros_are_rejected_with_a_suggestion() {
+    let (tokens, diagnostics) = lex("print 0;", IntegerWidth::I32);
+    assert!(diagnostics.is_empty());
+    assert_eq!(number(&tokens[1]), &Number::I32(0));
+
+    for (source, fixed) in [("print 007;", "7"), ("print 00;", "0")] {
+        let (tokens, diagnostics) = lex(source, IntegerWidth::I32);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some("E0005"));
+        assert_eq!(diagnostics[0].suggestions[0].replacement, fixed);
+        assert_eq!(number(&tokens[1]).to_string(), fixed);
+    }
+}
+
+#[test]
+fn wider_integers_can_be_selected() {
+    let (tokens, diagnostics) = lex("print 9223372036854775807;", IntegerWidth::I64);
+    assert!(diagnostics.is_empty());
+    assert_eq!(number(&tokens[1]), &Number::I64(i64::MAX));
+    assert_eq!(number(&tokens[1]).to_i32(), None);
+
+    let (_, diagnostics) = lex("print 9223372036854775808;", IntegerWidth::I64);
+    assert_eq!(diagnostics[0].code, Some("E0004"));
+    assert!(diagnostics[0].message.ends_with("de 64 bits"));
+
+    let literal = "123456789012345678901234567890";
+    let (tokens, diagnostics) = lex(&format!("print 000{};", literal), IntegerWidth::Arbitrary);
+    assert_eq!(diagnostics.len(), 1, "apenas os zeros à esquerda");
+    assert_eq!(number(&tokens[1]), &Number::Big(literal.to_string()));
+}
+
+/// Programa analisado com literais de largura `integer_width` e os diagnósticos semânticos.
+fn check(source: &str, integer_width: IntegerWidth) -> (Program, Vec<Diagnostic>) {
+    let (tokens, diagnostics) = lex(source, integer_width);
+    assert!(diagnostics.is_empty(), "erros léxicos: {:?}", diagnostics);
+    let program = parse(tokens).expect("o programa deveria ser sintaticamente válido");
+    let diagnostics = semantic::check(&program);
+    (program, diagnostics)
+}
+
+/// Valor do literal do comando `print` de um programa formado por um único comando.
+fn printed(program: &Program) -> &Number {
+    match &program.statement {
+        Some(Stmt::Print(Expr::Num { value, .. })) => value,
+        statement => panic!("comando inesperado: {:?}", statement),
+    }
+}
+
+#[test]
+fn the_ast_keeps_the_literal_width() {
+    let (program, diagnostics) = check("print 2147483647;", IntegerWidth::I64);
+    assert!(diagnostics.is_empty());
+    assert_eq!(printed(&program), &Number::I64(i32::MAX.into()));
+
+    let (program, diagnostics) = check("print\n  9223372036854775807;", IntegerWidth::I64);
+    assert_eq!(printed(&program), &Number::I64(i64::MAX));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Some("E0004"));
+    assert!(diagnostics[0].is_error());
+    assert_eq!(
+        diagnostics[0].message,
+        "literal inteiro `9223372036854775807` fora do intervalo dos inteiros de 32 bits da LSI-2024-2"
+    );
+    assert_eq!(
+        (diagnostics[0].span.line, diagnostics[0].span.column),
+        (2, 3)
+    );
+
+    let literal = "123456789012345678901234567890";
+    let (program, diagnostics) = check(&format!("print {};", literal), IntegerWidth::Arbitrary);
+    assert_eq!(printed(&program), &Number::Big(literal.to_string()));
+    assert_eq!(diagnostics[0].code, Some("E0004"));
+    assert_eq!(diagnostics[0].span.column, 7);
+
+    let (program, diagnostics) = check("print 42;", IntegerWidth::Arbitrary);
+    assert!(diagnostics.is_empty());
+    assert_eq!(printed(&program), &Number::Big("42".to_string()));
+}
+
+#[test]
+fn wide_literals_are_range_errors_on_the_command_line() {
+    let path = std::env::temp_dir().join(format!("lsi-literals-{}.lsi", std::process::id()));
+    std::fs::write(&path, "def principal() { print 4294967296; }").unwrap();
+    let run = |width: &str| {
+        Command::new(env!("CARGO_BIN_EXE_parser"))
+            .args(["--int-width", width])
+            .arg(&path)
+            .env("NO_COLOR", "1")
+            .output()
+            .unwrap()
+    };
+    let (wide, big) = (run("64"), run("big"));
+    std::fs::remove_file(&path).unwrap();
+
+    for output in [wide, big] {
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("erro[E0004]"), "{}", stderr);
+        assert!(stderr.contains("`4294967296` fora do intervalo dos inteiros de 32 bits"));
+        assert!(!stderr.contains("E0100"));
+    }
+}
@@ -0,0 +1,70 @@
+//! Testes da recuperação de erros sintáticos em modo pânico: todos os erros de uma entrada
+//! são reportados em uma única execução.
+use parser::{lsi_keywords, parse, tokenize};
+
+/// Linha, coluna e lexema encontrado de cada erro sintático da entrada.
+fn errors(source: &str) -> Vec<(usize, usize, Option<String>)> {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, diagnostics) = tokenize(source, &keywords, &mut symbol_table);
+    assert!(diagnostics.is_empty(), "erros léxicos: {:?}", diagnostics);
+    parse(tokens)
+        .expect_err("a análise deveria falhar")
+        .into_iter()
+        .map(|error| (error.span.line, error.span.column, error.found))
+        .collect()
+}
+
+fn found(line: usize, column: usize, lexeme: &str) -> (usize, usize, Option<String>) {
+    (line, column, Some(lexeme.to_string()))
+}
+
+#[test]
+fn every_error_is_reported_with_its_position() {
+    assert_eq!(
+        errors("def principal() {\n  int x;\n  x := 1 + ;\n  print x;\n  x := * 2;\n  return;\n}"),
+        [found(3, 12, ";"), found(5, 8, "*")]
+    );
+}
+
+#[test]
+fn parsing_resumes_after_a_semicolon() {
+    // O restante do comando é descartado até o `;`, e o comando seguinte é analisado
+    assert_eq!(
+        errors("{ x := 1 + ; print 2; print 3 4; }"),
+        [found(1, 12, ";"), found(1, 31, "4")]
+    );
+    assert_eq!(
+        errors("def f() { int a b c; print a; }\ndef g() { print 1 ) ; x := 2; }"),
+        [found(1, 17, "b"), found(2, 19, ")")]
+    );
+}
+
+#[test]
+fn parsing_resumes_after_a_closing_brace() {
+    // O erro no fim do corpo de `f` não impede a análise de `g`
+    assert_eq!(
+        errors("def f() { x := 1 + }\ndef g() { print 2 3; }"),
+        [found(1, 20, "}"), found(2, 19, "3")]
+    );
+    assert_eq!(
+        errors("def f() { int x y z }\ndef g() { print ; }"),
+        [found(1, 17, "y"), found(1, 21, "}"), found(2, 17, ";")]
+    );
+    // Um erro dentro de um bloco interno não afeta os comandos após o bloco
+    assert_eq!(
+        errors("def f() { if (x) { print ; } print y; }"),
+        [found(1, 26, ";")]
+    );
+}
+
+#[test]
+fn errors_at_the_end_of_the_input_are_reported() {
+    // O erro fica logo após o último token
+    assert_eq!(errors("def principal() { print 1;"), [(1, 27, None)]);
+    assert_eq!(errors("def principal() {\n  x := 1 +"), [(2, 11, None)]);
+    assert_eq!(
+        errors("def principal() { x := 1 + ; print"),
+        [found(1, 28, ";"), (1, 35, None)]
+    );
+}
@@ -0,0 +1,110 @@
+//! Operadores relacionais da LSI-2024-2 em todo o pipeline: análise léxica, sintática e AST.
+use std::collections::HashSet;
+
+use parser::ast::{Expr, RelOp, Stmt};
+use parser::lexer::{tokenize_with_options, LexerOptions};
+use parser::{lsi_keywords, parse, Severity, Terminal, Token};
+
+fn lex(source: &str, options: LexerOptions) -> (Vec<Token>, Vec<parser::Diagnostic>) {
+    let keywords = lsi_keywords();
+    let mut symbol_table: HashSet<String> = keywords.clone();
+    tokenize_with_options(source, &keywords, &mut symbol_table, options)
+}
+
+/// Programa com um único `if` cuja condição usa o operador `op`.
+fn program_with(op: &str) -> String {
+    format!("def principal(int a, int b) {{ if (a {} b) print a; }}", op)
+}
+
+/// Retorna o operador da condição do `if` do programa.
+fn condition_op(source: &str, options: LexerOptions) -> RelOp {
+    let (tokens, diagnostics) = lex(source, options);
+    assert!(
+        diagnostics.iter().all(|diagnostic| !diagnostic.is_error()),
+        "erros léxicos: {:?}",
+        diagnostics
+    );
+
+    let program = parse(tokens).expect("o programa deveria ser sintaticamente válido");
+    match &program.functions[0].body[0] {
+        Stmt::If {
+            cond: Expr::Relational { op, .. },
+            ..
+        } => *op,
+        stmt => panic!("comando inesperado: {:?}", stmt),
+    }
+}
+
+const RELOPS: [(&str, Terminal, RelOp); 6] = [
+    ("<", Terminal::LessThan, RelOp::Lt),
+    ("<=", Terminal::LessEqual, RelOp::Le),
+    (">", Terminal::GreaterThan, RelOp::Gt),
+    (">=", Terminal::GreaterEqual, RelOp::Ge),
+    ("==", Terminal::Equal, RelOp::Eq),
+    ("<>", Terminal::NotEqual, RelOp::Ne),
+];
+
+#[test]
+fn every_relop_is_lexed_as_a_single_token() {
+    for (symbol, terminal, _) in RELOPS {
+        let (tokens, diagnostics) = lex(&format!("a {} b", symbol), LexerOptions::default());
+        assert!(diagnostics.is_empty(), "{}: {:?}", symbol, diagnostics);
+        assert_eq!(tokens.len(), 3, "{}: {:?}", symbol, tokens);
+        assert_eq!(tokens[1].lexeme, symbol);
+        assert_eq!(tokens[1].terminal, terminal);
+    }
+}
+
+#[test]
+fn relops_are_lexed_without_surrounding_spaces() {
+    for (symbol, terminal, _) in RELOPS {
+        let (tokens, diagnostics) = lex(&format!("a{}b", symbol), LexerOptions::default());
+        assert!(diagnostics.is_empty(), "{}: {:?}", symbol, diagnostics);
+        let terminals: Vec<Terminal> = tokens.iter().map(|token| token.terminal).collect();
+        assert_eq!(terminals, [Terminal::Id, terminal, Terminal::Id]);
+    }
+}
+
+#[test]
+fn every_relop_reaches_the_ast() {
+    for (symbol, _, op) in RELOPS {
+        assert_eq!(
+            condition_op(&program_with(symbol), LexerOptions::default()),
+            op,
+            "{}",
+            symbol
+        );
+    }
+}
+
+#[test]
+fn bang_equal_is_an_error_by_default() {
+    let (tokens, diagnostics) = lex("a != b", LexerOptions::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert!(diagnostics[0].message.contains("<>"));
+    // O token ainda é produzido para que a análise sintática continue
+    assert_eq!(tokens[1].terminal, Terminal::NotEqual);
+}
+
+#[test]
+fn bang_equal_is_accepted_with_a_warning_in_compat_mode() {
+    let options = LexerOptions {
+        compat_not_equal: true,
+        ..LexerOptions::default()
+    };
+    let (_, diagnostics) = lex(&program_with("!="), options);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(condition_op(&program_with("!="), options), RelOp::Ne);
+}
+
+#[test]
+fn lone_bang_and_equal_are_not_relops() {
+    for source in ["a ! b", "a = b"] {
+        let (tokens, diagnostics) = lex(source, LexerOptions::default());
+        assert_eq!(diagnostics.len(), 1, "{}: {:?}", source, diagnostics);
+        assert!(diagnostics[0].is_error());
+        assert_eq!(tokens.len(), 2, "{}: {:?}", source, tokens);
+    }
+}
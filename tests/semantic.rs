@@ -0,0 +1,179 @@
+//! Testes da análise semântica: escopos de variáveis e parâmetros e verificação das chamadas
+//! de função.
+use parser::diagnostic::LabelStyle;
+use parser::{lsi_keywords, parse, semantic, tokenize, Diagnostic, Severity};
+
+fn check(source: &str) -> Vec<Diagnostic> {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, diagnostics) = tokenize(source, &keywords, &mut symbol_table);
+    assert!(diagnostics.is_empty(), "erros léxicos: {:?}", diagnostics);
+    let program = parse(tokens).expect("o programa deveria ser sintaticamente válido");
+    semantic::check(&program)
+}
+
+/// Código, linha e coluna de cada diagnóstico.
+fn summary(diagnostics: &[Diagnostic]) -> Vec<(&str, usize, usize)> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.code.unwrap_or(""),
+                diagnostic.span.line,
+                diagnostic.span.column,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn diagnostics_follow_program_order() {
+    let diagnostics = check("def principal() {\n  y := z;\n  print w;\n}");
+    assert_eq!(
+        summary(&diagnostics),
+        [("E0200", 2, 3), ("E0200", 2, 8), ("E0200", 3, 9)]
+    );
+}
+
+#[test]
+fn undeclared_variables_are_errors() {
+    let source = include_str!("../entrada-valida-1.lsi");
+    let diagnostics = check(source);
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert!(diagnostic.is_error());
+    assert_eq!(diagnostic.code, Some("E0200"));
+    assert_eq!(
+        diagnostic.message,
+        "variável `AvaliacaoAluno` não declarada"
+    );
+    assert_eq!((diagnostic.span.line, diagnostic.span.column), (42, 5));
+    assert_eq!(
+        &source[diagnostic.span.start..diagnostic.span.end],
+        "AvaliacaoAluno"
+    );
+    assert_eq!(
+        diagnostic.notes,
+        ["declare a variável antes do uso: `int AvaliacaoAluno;`"]
+    );
+
+    // Variáveis de um bloco não são visíveis depois dele
+    let diagnostics = check("def principal() {\n  { int x; x := 1; }\n  print x;\n}");
+    assert_eq!(summary(&diagnostics), [("E0200", 3, 9)]);
+    let diagnostics = check("def principal() {\n  if (1) int x; else x := 2;\n}");
+    assert_eq!(summary(&diagnostics), [("E0200", 2, 22)]);
+}
+
+#[test]
+fn redeclarations_in_the_same_scope_are_errors() {
+    let diagnostics = check("def principal() {\n  int a, b;\n  int c, a;\n}");
+    assert_eq!(summary(&diagnostics), [("E0201", 3, 10)]);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(
+        diagnostic.message,
+        "`a` já foi declarado neste escopo na linha 2, coluna 7"
+    );
+    let secondary = &diagnostic.labels[1];
+    assert_eq!(secondary.style, LabelStyle::Secondary);
+    assert_eq!((secondary.span.line, secondary.span.column), (2, 7));
+    assert_eq!(secondary.message, "primeira declaração");
+
+    // Escopos irmãos podem declarar o mesmo nome
+    assert!(check("def principal() { { int a; } { int a; } }").is_empty());
+}
+
+#[test]
+fn shadowing_is_a_warning_with_both_positions() {
+    let diagnostics = check("def principal() {\n  int x;\n  {\n    int x;\n    x := 1;\n  }\n}");
+    assert_eq!(summary(&diagnostics), [("W0200", 4, 9)]);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.severity, Severity::Warning);
+    assert_eq!(
+        diagnostic.message,
+        "a declaração de `x` oculta a declaração da linha 2, coluna 7"
+    );
+    let hidden = diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Secondary)
+        .unwrap();
+    assert_eq!((hidden.span.line, hidden.span.column), (2, 7));
+    assert_eq!(hidden.message, "declaração ocultada");
+}
+
+#[test]
+fn parameters_belong_to_the_function_scope() {
+    // Parâmetros são visíveis no corpo e compartilham o escopo com ele
+    assert!(check("def f(int a, int b) { int c; c := a + b; return c; }").is_empty());
+    let diagnostics = check("def f(int a) {\n  int a;\n  return a;\n}");
+    assert_eq!(summary(&diagnostics), [("E0201", 2, 7)]);
+    let diagnostics = check("def f(int a, int a) { return a; }");
+    assert_eq!(summary(&diagnostics), [("E0201", 1, 18)]);
+
+    // Um bloco interno pode ocultar o parâmetro
+    let diagnostics = check("def f(int a) {\n  { int a; }\n  return a;\n}");
+    assert_eq!(summary(&diagnostics), [("W0200", 2, 9)]);
+
+    // Os parâmetros de uma função não são visíveis em outra
+    let diagnostics = check("def f(int a) { return a; }\ndef g() { return a; }");
+    assert_eq!(summary(&diagnostics), [("E0200", 2, 18)]);
+}
+
+#[test]
+fn calls_to_undefined_functions_are_errors() {
+    let diagnostics = check("def principal() {\n  int x;\n  x := soma(x);\n}");
+    assert_eq!(summary(&diagnostics), [("E0202", 3, 8)]);
+    assert_eq!(diagnostics[0].message, "função `soma` não definida");
+    assert_eq!(diagnostics[0].labels[0].message, "função desconhecida");
+}
+
+#[test]
+fn calls_with_the_wrong_number_of_arguments_are_errors() {
+    let source = "def soma(int a, int b) {\n  int r;\n  r := a + b;\n  return r;\n}\ndef principal() {\n  int x;\n  x := soma(x);\n  x := soma(x, x, x);\n  x := soma(x, x);\n}";
+    let diagnostics = check(source);
+    assert_eq!(summary(&diagnostics), [("E0203", 8, 8), ("E0203", 9, 8)]);
+    assert_eq!(
+        diagnostics[0].message,
+        "a função `soma` espera 2 argumento(s), mas recebeu 1 (definida na linha 1, coluna 5)"
+    );
+    let labels: Vec<(LabelStyle, usize, &str)> = diagnostics[1]
+        .labels
+        .iter()
+        .map(|label| (label.style, label.span.line, label.message.as_str()))
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            (LabelStyle::Primary, 9, "3 argumento(s)"),
+            (LabelStyle::Secondary, 1, "definida aqui com 2 parâmetro(s)"),
+        ]
+    );
+}
+
+#[test]
+fn duplicate_definitions_are_errors() {
+    let source = "def f() {\n  return;\n}\ndef g() {\n  int x;\n  x := f();\n}\ndef f(int a) {\n  return a;\n}";
+    let diagnostics = check(source);
+    // A primeira definição vale: a chamada sem argumentos está correta
+    assert_eq!(summary(&diagnostics), [("E0204", 8, 5)]);
+    assert_eq!(
+        diagnostics[0].message,
+        "a função `f` já foi definida na linha 1, coluna 5"
+    );
+    assert_eq!(diagnostics[0].labels[1].span.line, 1);
+    assert_eq!(diagnostics[0].labels[1].message, "primeira definição");
+
+    // Funções duplicadas são relatadas antes dos erros do corpo das funções
+    let diagnostics = check("def f() {\n  print x;\n}\ndef f() {\n  return;\n}");
+    assert_eq!(summary(&diagnostics), [("E0204", 4, 5), ("E0200", 2, 9)]);
+}
+
+#[test]
+fn functions_can_be_called_before_their_definition() {
+    let source = "def principal() {\n  int x;\n  x := dobro(x);\n  print x;\n}\ndef dobro(int n) {\n  int r;\n  r := n * 2;\n  return r;\n}";
+    assert!(check(source).is_empty());
+
+    // Recursão mútua
+    let source = "def par(int n) { int r; r := impar(n); return r; }\ndef impar(int n) { int r; r := par(n); return r; }";
+    assert!(check(source).is_empty());
+}
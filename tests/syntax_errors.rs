@@ -0,0 +1,72 @@
+//! Testes das mensagens de erro sintático: terminais esperados obtidos da tabela de análise,
+//! o contexto declarado com `%describe` e as sugestões de correção.
+use parser::bnf::parse_grammar;
+use parser::ll1_table::ll1_transition_table;
+use parser::{lsi_keywords, parse, tokenize, SyntaxError};
+
+fn errors(source: &str) -> Vec<SyntaxError> {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, _) = tokenize(source, &keywords, &mut symbol_table);
+    parse(tokens).unwrap_err()
+}
+
+#[test]
+fn expected_terminals_come_from_the_table() {
+    let error = &errors("def f() {\n  int a b;\n  return;\n}")[0];
+    assert_eq!(
+        error.message,
+        "esperado `,` ou `;` após a lista de variáveis, encontrado `b`"
+    );
+    assert_eq!(error.expected, vec![",", ";"]);
+    assert_eq!(error.found.as_deref(), Some("b"));
+
+    let table = ll1_transition_table();
+    assert_eq!(table.expected("VARLIST'"), vec![",", ";"]);
+    assert_eq!(table.expected("MAIN").last(), Some(&"$"));
+    assert!(table.expected("TERM'").len() > 2);
+    assert!(errors("x := 1 +;")[0]
+        .message
+        .starts_with("esperado um de `(`, `id` ou `num` em uma expressão"));
+}
+
+#[test]
+fn missing_comma_between_arguments_is_suggested() {
+    let source = "def principal() {\n  int a;\n  a := verificaMaior(Lado1 Lado2);\n}";
+    let error = &errors(source)[0];
+    assert_eq!(
+        error.message,
+        "esperado `)` ou `,` após um argumento da chamada, encontrado `Lado2`"
+    );
+    let suggestion = error.suggestion.as_ref().unwrap();
+    assert_eq!(suggestion.message, "adicione `,` entre `Lado1` e `Lado2`");
+    assert_eq!(suggestion.replacement, ",");
+    assert_eq!((suggestion.span.line, suggestion.span.column), (3, 27));
+    assert_eq!(suggestion.span.start, suggestion.span.end);
+    assert_eq!(suggestion.span.start, source.find(" Lado2").unwrap());
+    assert_eq!(error.to_diagnostic().suggestions.len(), 1);
+}
+
+#[test]
+fn missing_semicolon_at_the_end_of_the_line_is_suggested() {
+    let error = &errors("def principal() {\n  print 1\n}")[0];
+    let suggestion = error.suggestion.as_ref().unwrap();
+    assert_eq!(suggestion.message, "adicione `;` após `1`");
+    assert_eq!((suggestion.span.line, suggestion.span.column), (2, 10));
+
+    // `;` no início de um comando seria um comando vazio, não uma correção
+    assert!(errors("def principal() {")[0].suggestion.is_none());
+}
+
+#[test]
+fn describe_directive_is_validated() {
+    let (grammar, _) =
+        parse_grammar("S -> a S' \nS' -> b | ε\n%describe S' depois de `a`").unwrap();
+    assert_eq!(grammar.descriptions["S'"], "depois de `a`");
+
+    let diagnostics = parse_grammar("S -> a\n%describe T no início").unwrap_err();
+    assert!(diagnostics[0]
+        .message
+        .contains("`%describe` se refere a `T`"));
+    assert!(parse_grammar("S -> a\n%describe S").is_err());
+}
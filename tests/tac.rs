@@ -0,0 +1,173 @@
+//! Testes do código de três endereços: o texto impresso por `--emit tac` é comparado com a
+//! tradução esperada de cada construção.
+use parser::{lsi_keywords, parse, semantic, tac, tokenize, Program};
+
+fn program(source: &str) -> Program {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, diagnostics) = tokenize(source, &keywords, &mut symbol_table);
+    assert!(diagnostics.is_empty(), "erros léxicos: {:?}", diagnostics);
+    parse(tokens).expect("o programa deveria ser sintaticamente válido")
+}
+
+fn tac(source: &str) -> String {
+    let program = program(source);
+    let diagnostics = semantic::check(&program);
+    assert!(
+        diagnostics.iter().all(|diagnostic| !diagnostic.is_error()),
+        "erros semânticos: {:?}",
+        diagnostics
+    );
+    tac::lower(&program).to_string()
+}
+
+#[test]
+fn expressions_use_temporaries() {
+    assert_eq!(
+        tac("def f(int a, int b) {
+            int r;
+            r := a + b * 2 - (a - 1) / b;
+            print r >= 10;
+            print a;
+            return r;
+        }"),
+        "\
+function f(a, b):
+    r = 0
+    t1 = b * 2
+    t2 = a + t1
+    t3 = a - 1
+    t4 = t3 / b
+    r = t2 - t4
+    t5 = r >= 10
+    print t5
+    print a
+    return r
+"
+    );
+}
+
+#[test]
+fn if_and_else_become_conditional_jumps() {
+    assert_eq!(
+        tac("def principal() {
+            int x, y;
+            if (x < y) print x; else print y;
+            if (x - 3) { x := 1; }
+            if (x) print x;
+            print 0;
+        }"),
+        "\
+function principal():
+    x = 0
+    y = 0
+    ifFalse x < y goto L1
+    print x
+    goto L2
+L1:
+    print y
+L2:
+    t1 = x - 3
+    ifFalse t1 <> 0 goto L3
+    x = 1
+L3:
+    ifFalse x <> 0 goto L4
+    print x
+L4:
+    print 0
+    return
+"
+    );
+}
+
+#[test]
+fn calls_pass_parameters_in_order() {
+    assert_eq!(
+        tac("def soma(int a, int b, int c) {
+            int r;
+            r := a + b + c;
+            return r;
+        }
+        def principal() {
+            int x, y;
+            x := soma(x, y, x);
+            y := nada();
+            return x;
+        }
+        def nada() { return; }"),
+        "\
+function soma(a, b, c):
+    r = 0
+    t1 = a + b
+    r = t1 + c
+    return r
+
+function principal():
+    x = 0
+    y = 0
+    param x
+    param y
+    param x
+    x = call soma, 3
+    y = call nada, 0
+    return x
+
+function nada():
+    return
+"
+    );
+}
+
+#[test]
+fn shadowed_variables_are_renamed() {
+    assert_eq!(
+        tac("def principal(int x) {
+            int y;
+            if (x == 1) { int x; x := 2; print x; } else { int x, y; y := x; }
+            { int x; { int x; x := 3; } print x; }
+            print x;
+            print y;
+        }"),
+        "\
+function principal(x):
+    y = 0
+    ifFalse x == 1 goto L1
+    x.1 = 0
+    x.1 = 2
+    print x.1
+    goto L2
+L1:
+    x.2 = 0
+    y.1 = 0
+    y.1 = x.2
+L2:
+    x.3 = 0
+    x.4 = 0
+    x.4 = 3
+    print x.3
+    print x
+    print y
+    return
+"
+    );
+}
+
+#[test]
+fn a_single_statement_becomes_principal() {
+    assert_eq!(
+        tac("{ int x; x := 2 * 21; print x; }"),
+        "\
+function principal():
+    x = 0
+    x = 2 * 21
+    print x
+    return
+"
+    );
+}
+
+#[test]
+#[should_panic(expected = "variável `z` não declarada")]
+fn programs_must_pass_semantic_analysis() {
+    tac::lower(&program("def principal() { print z; }"));
+}
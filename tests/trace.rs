@@ -0,0 +1,116 @@
+//! Testes do observador da análise sintática: a sequência de eventos notificada por
+//! `parse_with_observer` e a saída da linha de comando com e sem `--trace`.
+use std::process::Command;
+
+use parser::parser::{parse_with_observer, ParseEvent, ParseObserver};
+use parser::{lsi_keywords, tokenize};
+
+/// Observador que guarda uma descrição curta de cada evento.
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+}
+
+impl ParseObserver for Recorder {
+    fn on_event(&mut self, event: ParseEvent) {
+        self.events.push(match event {
+            ParseEvent::Expand { production, token } => match token {
+                Some(token) => format!("expand {} @ {}", production, token.lexeme),
+                None => format!("expand {} @ $", production),
+            },
+            ParseEvent::Match { terminal, token } => format!("match {} {}", terminal, token.lexeme),
+            ParseEvent::Error(error) => format!("error {:?}", error.found),
+            ParseEvent::Pop { symbol } => format!("pop {}", symbol),
+            ParseEvent::Skip { token } => format!("skip {}", token.lexeme),
+            ParseEvent::Resume { nonterminal, token } => {
+                format!("resume {} @ {}", nonterminal, token.lexeme)
+            }
+        });
+    }
+}
+
+fn events(source: &str) -> Vec<String> {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, _) = tokenize(source, &keywords, &mut symbol_table);
+    let mut recorder = Recorder::default();
+    let _ = parse_with_observer(tokens, &mut recorder);
+    recorder.events
+}
+
+#[test]
+fn every_step_is_notified_in_order() {
+    assert_eq!(
+        events("print 1;"),
+        [
+            "expand MAIN -> STMT @ print",
+            "expand STMT -> PRINTST ; @ print",
+            "expand PRINTST -> print EXPR @ print",
+            "match print print",
+            "expand EXPR -> NUMEXPR EXPR' @ 1",
+            "expand NUMEXPR -> TERM NUMEXPR' @ 1",
+            "expand TERM -> FACT TERM' @ 1",
+            "expand FACT -> num @ 1",
+            "match num 1",
+            "expand TERM' -> ε @ ;",
+            "expand NUMEXPR' -> ε @ ;",
+            "expand EXPR' -> ε @ ;",
+            "match ; ;",
+        ]
+    );
+}
+
+/// Eventos da recuperação de erros, sem as expansões e os terminais casados.
+fn recovery(source: &str) -> Vec<String> {
+    events(source)
+        .into_iter()
+        .filter(|event| !event.starts_with("expand") && !event.starts_with("match"))
+        .collect()
+}
+
+#[test]
+fn recovery_steps_are_notified() {
+    // `+` não inicia nem segue uma expressão: é descartado, e a expressão continua em `1`
+    assert_eq!(
+        recovery("{ print + 1; }"),
+        ["error Some(\"+\")", "skip +", "resume EXPR @ 1"]
+    );
+    // `)` segue uma expressão: a expressão e o `;` saem da pilha, e os tokens são descartados
+    // até o `;`, que continua a lista de comandos
+    assert_eq!(
+        recovery("{ print ) 1; }"),
+        [
+            "error Some(\")\")",
+            "pop EXPR",
+            "pop ;",
+            "skip )",
+            "skip 1",
+            "resume STMTLIST' @ ;"
+        ]
+    );
+}
+
+fn check(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_parser"))
+        .args(args)
+        .arg("entrada-valida-2.lsi")
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn the_parser_is_silent_by_default() {
+    assert_eq!(check(&[]), "Análise sintática bem-sucedida.\n");
+
+    let trace = check(&["--trace"]);
+    assert!(trace.starts_with("Rastro da análise sintática:\nAplicando regra: MAIN -> FLIST"));
+    assert!(trace.contains("Terminal correspondente: def `def` na linha 1, coluna 1"));
+    assert!(!trace.contains("Lista de tokens:"));
+
+    let dump = check(&["--dump"]);
+    assert!(dump.starts_with("Lista de tokens:\n"));
+    assert!(dump.contains("Árvore sintática abstrata:\nProgram {"));
+    assert!(!dump.contains("Aplicando regra"));
+}
@@ -0,0 +1,112 @@
+//! Testes estruturais do módulo WebAssembly em formato de texto gerado por `--emit wat`.
+use parser::{lsi_keywords, parse, wat};
+
+fn generate(source: &str) -> String {
+    let keywords = lsi_keywords();
+    let mut symbol_table = keywords.clone();
+    let (tokens, diagnostics) = parser::tokenize(source, &keywords, &mut symbol_table);
+    assert!(diagnostics.is_empty(), "erros léxicos: {:?}", diagnostics);
+    wat::generate(&parse(tokens).expect("o programa deveria ser sintaticamente válido"))
+}
+
+/// Verifica que os parênteses do módulo estão balanceados e que ele é uma única expressão `module`.
+fn assert_balanced(module: &str) {
+    assert!(module.starts_with("(module\n"));
+    let mut depth = 0i32;
+    for (index, ch) in module.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        assert!(depth >= 0, "`)` sem `(` correspondente no offset {}", index);
+        if depth == 0 {
+            assert_eq!(module[index + 1..].trim(), "", "conteúdo após o módulo");
+            return;
+        }
+    }
+    panic!("módulo não foi fechado");
+}
+
+/// Linhas da função `name`, do cabeçalho até o fim.
+fn function<'a>(module: &'a str, name: &str) -> Vec<&'a str> {
+    let header = format!("  (func ${} ", name);
+    let mut lines = module.lines().skip_while(|line| !line.starts_with(&header));
+    let first = lines.next().expect("função não encontrada");
+    std::iter::once(first)
+        .chain(lines.take_while(|line| !line.is_empty() && *line != ")"))
+        .collect()
+}
+
+#[test]
+fn one_exported_function_per_definition() {
+    let module = generate(
+        "def soma(int a, int b) { int r; r := a + b; return r; }
+        def principal() { int x, y; x := 1; y := soma(x, x); print y; return; }",
+    );
+    assert_balanced(&module);
+    assert!(module.contains("(import \"env\" \"print\" (func $runtime_print (param i32)))"));
+    assert_eq!(module.matches("(export ").count(), 2);
+
+    let soma = function(&module, "soma");
+    assert_eq!(
+        soma[0],
+        "  (func $soma (export \"soma\") (param $a i32) (param $b i32) (result i32)"
+    );
+    assert_eq!(soma[1], "    (local $r i32)");
+    assert!(soma.contains(&"    call $runtime_add"));
+
+    let principal = function(&module, "principal");
+    assert_eq!(
+        principal[0],
+        "  (func $principal (export \"principal\") (result i32)"
+    );
+    assert_eq!(
+        &principal[1..3],
+        ["    (local $x i32)", "    (local $y i32)"]
+    );
+    assert!(principal.contains(&"    call $soma"));
+    assert!(principal.contains(&"    call $runtime_print"));
+    assert!(principal.last().unwrap().ends_with("return)"));
+}
+
+#[test]
+fn shadowed_variables_get_their_own_locals() {
+    let module = generate(
+        "def principal() {
+            int x;
+            x := 1;
+            if (x > 0) { int x; x := 2; print x; } else print x;
+            { int x; x := 3; }
+            print x;
+            return x;
+        }",
+    );
+    assert_balanced(&module);
+    let principal = function(&module, "principal");
+    assert_eq!(
+        &principal[1..4],
+        [
+            "    (local $x i32)",
+            "    (local $x.1 i32)",
+            "    (local $x.2 i32)"
+        ]
+    );
+    assert!(principal.contains(&"    i32.gt_s"));
+    assert!(principal.contains(&"      local.set $x.1"));
+    assert!(principal.contains(&"    local.set $x.2"));
+    assert!(principal.contains(&"    else"));
+    assert!(principal.contains(&"      local.get $x"));
+}
+
+#[test]
+fn statement_program_becomes_principal() {
+    let module = generate("print 1 / 2;");
+    assert_balanced(&module);
+    let principal = function(&module, "principal");
+    assert_eq!(
+        principal[0],
+        "  (func $principal (export \"principal\") (result i32)"
+    );
+    assert!(principal.contains(&"    call $runtime_div"));
+}
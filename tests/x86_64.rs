@@ -0,0 +1,78 @@
+//! Testes de ponta a ponta do backend x86-64: o assembly gerado é montado e ligado com o
+//! `cc` do sistema, e a saída do executável é comparada com a do interpretador.
+mod common;
+
+use common::Native;
+use parser::{tac, x86_64};
+
+const X86_64: Native = Native {
+    name: "x86_64",
+    extension: "s",
+    flags: &[],
+    generate: |program| x86_64::generate(&tac::lower(program), "principal").unwrap(),
+};
+
+fn assert_same_behaviour(name: &str, source: &str) {
+    common::assert_same_behaviour(&X86_64, name, source);
+}
+
+#[test]
+fn recursion_and_control_flow() {
+    assert_same_behaviour(
+        "fatorial",
+        "def fat(int n) {
+            int r, m, k;
+            if (n <= 1) { r := 1; return r; }
+            m := n - 1;
+            k := fat(m);
+            r := n * k;
+            return r;
+        }
+        def principal() {
+            int x, y;
+            x := 10;
+            y := fat(x);
+            print y;
+            if (y <> 3628800) print 0; else { int x; x := 7; print x; }
+            print x;
+            print (7 - 20) / 2;
+            print x >= 10;
+            return;
+        }",
+    );
+}
+
+#[test]
+fn arguments_beyond_registers() {
+    assert_same_behaviour(
+        "argumentos",
+        "def soma(int a, int b, int c, int d, int e, int f, int g, int h) {
+            int r;
+            r := a + b * 10 + c * 100 + d + e + f + g * 1000 + h * 10000;
+            return r;
+        }
+        def principal() {
+            int a, b, c, d, e, f, g, h, x;
+            a := 1; b := 2; c := 3; d := 4; e := 5; f := 6; g := 7; h := 8;
+            x := soma(a, b, c, d, e, f, g, h);
+            print x;
+            return;
+        }",
+    );
+}
+
+#[test]
+fn runtime_errors_stop_the_program() {
+    assert_same_behaviour(
+        "divisao",
+        "def principal() { int z; print 1; print 1 / z; print 2; return; }",
+    );
+    assert_same_behaviour(
+        "estouro",
+        "def principal() { int x; x := 2147483647; print x; x := x + 1; print x; return; }",
+    );
+    assert_same_behaviour(
+        "recursao",
+        "def principal() { int b; b := principal(); return b; }",
+    );
+}